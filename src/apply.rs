@@ -6,12 +6,10 @@ use std::{fs, path::PathBuf, process::Command};
 
 pub fn cmd_build(cli: &Cli) -> Result<()> {
     let loaded = load::load_all(&cli.config_dir)?;
-    validate::validate_model(
-        &loaded.domains,
-        &loaded.upstreams,
-        &loaded.policies,
-        &loaded.defaults,
-    )?;
+    validate::validate_loaded(&loaded)?;
+    for warning in validate::lint_model(&loaded) {
+        eprintln!("warning: {}", warning);
+    }
 
     let yaml_value = generate::generate_envoy_yaml(&loaded)?;
 
@@ -72,12 +70,24 @@ fn add_section_comments(yaml: &str, loaded: &load::Loaded) -> String {
             );
         }
 
+        // Add comment before overload protection (common/overload.yaml)
+        if line == "overload_manager:" {
+            result.push_str("\n# -----------------------------------------------------------------------------\n");
+            result.push_str("# Overload Manager (resource monitors & actions)\n");
+            result.push_str(
+                "# -----------------------------------------------------------------------------\n",
+            );
+        }
+
         // Add comment before HTTP listener
         if line.trim_start() == "- name: http_listener" {
             result.push_str(&format!(
                 "{indent_str}# -----------------------------------------------------------------\n"
             ));
-            result.push_str(&format!("{indent_str}# HTTP Listener (Port 80)\n"));
+            result.push_str(&format!(
+                "{indent_str}# HTTP Listener (Port {})\n",
+                loaded.defaults.http_port()
+            ));
             result.push_str(&format!(
                 "{}# Default upstream: {}\n",
                 indent_str, loaded.defaults.http_default_upstream
@@ -94,7 +104,11 @@ fn add_section_comments(yaml: &str, loaded: &load::Loaded) -> String {
                 "\n{}# -----------------------------------------------------------------\n",
                 indent_str
             ));
-            result.push_str(&format!("{}# HTTPS SNI Listener (Port 443)\n", indent_str));
+            result.push_str(&format!(
+                "{}# HTTPS SNI Listener (Port {})\n",
+                indent_str,
+                loaded.defaults.https_port()
+            ));
             result.push_str(&format!(
                 "{}# TLS termination for configured domains, passthrough for others\n",
                 indent_str
@@ -1,67 +1,440 @@
 use crate::model::*;
 use anyhow::Result;
 use serde_yaml::{Mapping, Value};
+use std::collections::HashSet;
+use std::net::IpAddr;
 
 pub fn generate_envoy_yaml(loaded: &crate::load::Loaded) -> Result<Value> {
     let mut root = Mapping::new();
 
+    // --- node ---
+    // Zipkin spans take their service name from the node's cluster
+    if let Some(tracing) = loaded
+        .tracing
+        .as_ref()
+        .filter(|t| t.provider == TracingProvider::Zipkin)
+    {
+        let mut node = Mapping::new();
+        node.insert(s("cluster"), s(&tracing.service_name));
+        root.insert(s("node"), Value::Mapping(node));
+    }
+
     // --- admin ---
     root.insert(s("admin"), gen_admin(&loaded.admin, &loaded.access_log));
 
     // --- static_resources ---
     let mut static_resources = Mapping::new();
-    static_resources.insert(
-        s("listeners"),
-        gen_listeners(
-            &loaded.defaults,
-            &loaded.access_log,
-            &loaded.domains,
-            &loaded.policies,
-            &loaded.listeners,
-        ),
+    let mut listeners = gen_listeners(
+        &loaded.defaults,
+        &loaded.access_log,
+        loaded.tracing.as_ref(),
+        &loaded.domains,
+        &loaded.policies,
+        &loaded.listeners,
+        &loaded.certs,
     );
-    static_resources.insert(s("clusters"), gen_clusters(&loaded.upstreams));
+    let mut clusters = gen_clusters(&loaded.upstreams);
+    if let Some(readonly) = &loaded.admin.readonly_listener {
+        if let (Value::Sequence(l), Value::Sequence(c)) = (&mut listeners, &mut clusters) {
+            l.push(Value::Mapping(gen_admin_readonly_listener(
+                readonly,
+                &loaded.access_log,
+            )));
+            c.push(Value::Mapping(gen_admin_cluster(&loaded.admin)));
+        }
+    }
+    static_resources.insert(s("listeners"), listeners);
+    static_resources.insert(s("clusters"), clusters);
 
     root.insert(s("static_resources"), Value::Mapping(static_resources));
+
+    // --- stats ---
+    if let Some(config) = gen_stats_config(&loaded.stats) {
+        root.insert(s("stats_config"), config);
+    }
+    if !loaded.stats.sinks.is_empty() {
+        root.insert(
+            s("stats_sinks"),
+            Value::Sequence(loaded.stats.sinks.iter().map(stats_sink).collect()),
+        );
+    }
+
+    // --- overload protection ---
+    if let Some(manager) = gen_overload_manager(&loaded.overload) {
+        root.insert(s("overload_manager"), manager);
+    }
+    let listener_caps = listener_connection_limits(loaded);
+    let global_cap = loaded.overload.global_downstream_max_connections;
+    if global_cap.is_some() || !listener_caps.is_empty() {
+        root.insert(
+            s("layered_runtime"),
+            gen_layered_runtime(global_cap, &listener_caps),
+        );
+    }
     Ok(Value::Mapping(root))
 }
 
 fn gen_admin(admin: &AdminSpec, log: &AccessLogSpec) -> Value {
     let mut m = Mapping::new();
-    m.insert(s("access_log"), stdout_access_log(log));
-    m.insert(s("address"), socket_addr("TCP", &admin.address, admin.port));
+    m.insert(
+        s("access_log"),
+        access_log(admin.access_log.as_ref().unwrap_or(log), "admin"),
+    );
+    m.insert(s("address"), admin_address(admin, &admin.address));
+    if let Some(path) = &admin.profile_path {
+        m.insert(s("profile_path"), s(path));
+    }
+    Value::Mapping(m)
+}
+
+/// Admin endpoints that are safe to serve publicly: no config dump, no
+/// runtime or health status changes.
+const ADMIN_READONLY_PATHS: [&str; 2] = ["/ready", "/stats/prometheus"];
+
+const ADMIN_CLUSTER: &str = "envoy_admin";
+
+/// The admin socket, or its TCP address with `host` as the IP.
+fn admin_address(admin: &AdminSpec, host: &str) -> Value {
+    match &admin.socket_path {
+        Some(path) => {
+            let mut pipe = Mapping::new();
+            pipe.insert(s("path"), s(path));
+            let mut a = Mapping::new();
+            a.insert(s("pipe"), Value::Mapping(pipe));
+            Value::Mapping(a)
+        }
+        None => socket_addr("TCP", host, admin.port),
+    }
+}
+
+fn gen_admin_readonly_listener(
+    readonly: &AdminReadonlyListenerSpec,
+    log: &AccessLogSpec,
+) -> Mapping {
+    let mut listener = Mapping::new();
+    listener.insert(s("name"), s("admin_readonly_listener"));
+    listener.insert(
+        s("address"),
+        socket_addr("TCP", &readonly.address, readonly.port),
+    );
+
+    // Exact paths only; anything else gets Envoy's 404
+    let routes = ADMIN_READONLY_PATHS
+        .iter()
+        .map(|path| {
+            let mut m = Mapping::new();
+            m.insert(s("path"), s(*path));
+            let mut r = Mapping::new();
+            r.insert(s("cluster"), s(ADMIN_CLUSTER));
+            let mut route = Mapping::new();
+            route.insert(s("match"), Value::Mapping(m));
+            route.insert(s("route"), Value::Mapping(r));
+            Value::Mapping(route)
+        })
+        .collect();
+    let rc = route_config_single_vhost(
+        "admin_readonly_route",
+        "admin_readonly",
+        vec!["*"],
+        routes,
+        None,
+        Mapping::new(),
+    );
+    let hcm = http_connection_manager(
+        "admin_readonly",
+        log,
+        rc,
+        vec![http_filter_router()],
+        None,
+        None,
+    );
+    listener.insert(
+        s("filter_chains"),
+        Value::Sequence(vec![Value::Mapping(filter_chain_http(hcm))]),
+    );
+    listener
+}
+
+/// Static cluster pointing back at the admin interface
+fn gen_admin_cluster(admin: &AdminSpec) -> Mapping {
+    // A wildcard bind is reached over loopback
+    let host = match admin.address.as_str() {
+        "0.0.0.0" => "127.0.0.1",
+        "::" => "::1",
+        other => other,
+    };
+    let mut endpoint = Mapping::new();
+    endpoint.insert(s("address"), admin_address(admin, host));
+    let mut lb_endpoint = Mapping::new();
+    lb_endpoint.insert(s("endpoint"), Value::Mapping(endpoint));
+    let mut locality = Mapping::new();
+    locality.insert(
+        s("lb_endpoints"),
+        Value::Sequence(vec![Value::Mapping(lb_endpoint)]),
+    );
+    let mut load_assignment = Mapping::new();
+    load_assignment.insert(s("cluster_name"), s(ADMIN_CLUSTER));
+    load_assignment.insert(
+        s("endpoints"),
+        Value::Sequence(vec![Value::Mapping(locality)]),
+    );
+
+    let mut m = Mapping::new();
+    m.insert(s("name"), s(ADMIN_CLUSTER));
+    m.insert(s("connect_timeout"), s("1s"));
+    m.insert(s("type"), s("STATIC"));
+    m.insert(s("load_assignment"), Value::Mapping(load_assignment));
+    m
+}
+
+fn gen_stats_config(stats: &StatsSpec) -> Option<Value> {
+    let mut m = Mapping::new();
+    if !stats.tags.is_empty() {
+        let tags = stats
+            .tags
+            .iter()
+            .map(|tag| {
+                let mut t = Mapping::new();
+                t.insert(s("tag_name"), s(&tag.tag_name));
+                if let Some(regex) = &tag.regex {
+                    t.insert(s("regex"), s(regex));
+                } else if let Some(value) = &tag.fixed_value {
+                    t.insert(s("fixed_value"), s(value));
+                }
+                Value::Mapping(t)
+            })
+            .collect();
+        m.insert(s("stats_tags"), Value::Sequence(tags));
+    }
+    if let Some(v) = stats.use_all_default_tags {
+        m.insert(s("use_all_default_tags"), b(v));
+    }
+    let (list, patterns) = if !stats.include.is_empty() {
+        ("inclusion_list", &stats.include)
+    } else {
+        ("exclusion_list", &stats.exclude)
+    };
+    if !patterns.is_empty() {
+        let mut l = Mapping::new();
+        l.insert(
+            s("patterns"),
+            Value::Sequence(patterns.iter().map(string_matcher).collect()),
+        );
+        let mut matcher = Mapping::new();
+        matcher.insert(s(list), Value::Mapping(l));
+        m.insert(s("stats_matcher"), Value::Mapping(matcher));
+    }
+    (!m.is_empty()).then_some(Value::Mapping(m))
+}
+
+const FIXED_HEAP_MONITOR: &str = "envoy.resource_monitors.fixed_heap";
+
+fn gen_overload_manager(overload: &OverloadSpec) -> Option<Value> {
+    let mut monitors = Vec::new();
+    if let Some(bytes) = overload.max_heap_size_bytes {
+        let mut tc = Mapping::new();
+        tc.insert(
+            s("@type"),
+            s("type.googleapis.com/envoy.extensions.resource_monitors.fixed_heap.v3.FixedHeapConfig"),
+        );
+        tc.insert(s("max_heap_size_bytes"), n(bytes));
+        let mut m = Mapping::new();
+        m.insert(s("name"), s(FIXED_HEAP_MONITOR));
+        m.insert(s("typed_config"), Value::Mapping(tc));
+        monitors.push(Value::Mapping(m));
+    }
+    if let Some(max) = overload.max_active_downstream_connections {
+        let mut tc = Mapping::new();
+        tc.insert(
+            s("@type"),
+            s("type.googleapis.com/envoy.extensions.resource_monitors.downstream_connections.v3.DownstreamConnectionsConfig"),
+        );
+        tc.insert(s("max_active_downstream_connections"), n(max));
+        let mut m = Mapping::new();
+        m.insert(
+            s("name"),
+            s("envoy.resource_monitors.global_downstream_max_connections"),
+        );
+        m.insert(s("typed_config"), Value::Mapping(tc));
+        monitors.push(Value::Mapping(m));
+    }
+    if monitors.is_empty() {
+        return None;
+    }
+
+    let actions: Vec<Value> = overload
+        .actions
+        .iter()
+        .map(|a| {
+            let mut threshold = Mapping::new();
+            threshold.insert(s("value"), Value::from(a.heap_threshold));
+            let mut trigger = Mapping::new();
+            trigger.insert(s("name"), s(FIXED_HEAP_MONITOR));
+            trigger.insert(s("threshold"), Value::Mapping(threshold));
+            let mut m = Mapping::new();
+            m.insert(
+                s("name"),
+                s(format!("envoy.overload_actions.{}", a.action.as_str())),
+            );
+            m.insert(
+                s("triggers"),
+                Value::Sequence(vec![Value::Mapping(trigger)]),
+            );
+            Value::Mapping(m)
+        })
+        .collect();
+
+    let mut m = Mapping::new();
+    if let Some(interval) = &overload.refresh_interval {
+        m.insert(s("refresh_interval"), s(interval));
+    }
+    m.insert(s("resource_monitors"), Value::Sequence(monitors));
+    if !actions.is_empty() {
+        m.insert(s("actions"), Value::Sequence(actions));
+    }
+    Some(Value::Mapping(m))
+}
+
+/// `max_connections` of every listener that sets one, by listener name.
+fn listener_connection_limits(loaded: &crate::load::Loaded) -> Vec<(String, u64)> {
+    let defaults = &loaded.defaults;
+    let mut caps = Vec::new();
+    if let Some(max) = defaults.listener_limits.max_connections {
+        if defaults.http_listener.enabled {
+            caps.push(("http_listener".to_string(), max));
+        }
+        if defaults.https_listener.enabled {
+            caps.push(("https_sni_listener".to_string(), max));
+        }
+    }
+    for l in &loaded.listeners.internal_http_listeners {
+        let limits = l.limits.as_ref().unwrap_or(&defaults.listener_limits);
+        if let Some(max) = limits.max_connections {
+            caps.push((l.name.clone(), max));
+        }
+    }
+    caps
+}
+
+/// Static runtime layer carrying the connection limits, followed by the admin
+/// layer so /runtime_modify keeps working once layers are declared. Listener
+/// caps use `envoy.resource_limits.listener.<name>.connection_limit`, which
+/// counts every connection of the listener whatever filter chain it lands on.
+fn gen_layered_runtime(
+    global_downstream_max_connections: Option<u64>,
+    listener_caps: &[(String, u64)],
+) -> Value {
+    let mut static_layer = Mapping::new();
+    if !listener_caps.is_empty() {
+        let mut listeners = Mapping::new();
+        for (name, max) in listener_caps {
+            let mut limit = Mapping::new();
+            limit.insert(s("connection_limit"), n(*max));
+            listeners.insert(s(name), Value::Mapping(limit));
+        }
+        let mut resource_limits = Mapping::new();
+        resource_limits.insert(s("listener"), Value::Mapping(listeners));
+        let mut envoy = Mapping::new();
+        envoy.insert(s("resource_limits"), Value::Mapping(resource_limits));
+        static_layer.insert(s("envoy"), Value::Mapping(envoy));
+    }
+    if let Some(max) = global_downstream_max_connections {
+        let mut overload = Mapping::new();
+        overload.insert(s("global_downstream_max_connections"), n(max));
+        static_layer.insert(s("overload"), Value::Mapping(overload));
+    }
+    let mut base = Mapping::new();
+    base.insert(s("name"), s("static_layer"));
+    base.insert(s("static_layer"), Value::Mapping(static_layer));
+
+    let mut admin = Mapping::new();
+    admin.insert(s("name"), s("admin_layer"));
+    admin.insert(s("admin_layer"), Value::Mapping(Mapping::new()));
+
+    let mut m = Mapping::new();
+    m.insert(
+        s("layers"),
+        Value::Sequence(vec![Value::Mapping(base), Value::Mapping(admin)]),
+    );
+    Value::Mapping(m)
+}
+
+fn stats_sink(sink: &StatsSinkSpec) -> Value {
+    let mut tc = Mapping::new();
+    let name = match sink.r#type {
+        StatsSinkType::Statsd => {
+            tc.insert(
+                s("@type"),
+                s("type.googleapis.com/envoy.config.metrics.v3.StatsdSink"),
+            );
+            "envoy.stat_sinks.statsd"
+        }
+        StatsSinkType::Dogstatsd => {
+            tc.insert(
+                s("@type"),
+                s("type.googleapis.com/envoy.config.metrics.v3.DogStatsdSink"),
+            );
+            "envoy.stat_sinks.dog_statsd"
+        }
+    };
+    if let Some(cluster) = &sink.upstream {
+        tc.insert(s("tcp_cluster_name"), s(cluster));
+    } else {
+        let address = sink.address.as_deref().expect("validated: address set");
+        let port = sink.port.expect("validated: port set");
+        tc.insert(s("address"), socket_addr("UDP", address, port));
+    }
+    if let Some(prefix) = &sink.prefix {
+        tc.insert(s("prefix"), s(prefix));
+    }
+
+    let mut m = Mapping::new();
+    m.insert(s("name"), s(name));
+    m.insert(s("typed_config"), Value::Mapping(tc));
     Value::Mapping(m)
 }
 
 fn gen_listeners(
     defaults: &DefaultsSpec,
     log: &AccessLogSpec,
+    tracing: Option<&TracingSpec>,
     domains: &[DomainSpec],
     policies: &PoliciesSpec,
     listeners_spec: &ListenersSpec,
+    cert_sets: &[CertSetSpec],
 ) -> Value {
     // :80 HTTP -> defaults.http_default_upstream
     // :443 TLS inspector + SNI split:
     // - terminate for domains with mode terminate_https_443
     // - default passthrough -> defaults.tls_passthrough_upstream
-    let mut listeners = vec![
-        Value::Mapping(gen_http_80_listener(defaults, log)),
-        Value::Mapping(gen_https_443_sni_listener(defaults, log, domains, policies)),
-    ];
+    // Either can be moved or switched off in defaults.http_listener/https_listener.
+    let mut listeners = Vec::new();
+    if defaults.http_listener.enabled {
+        listeners.push(Value::Mapping(gen_http_80_listener(defaults, log, tracing)));
+    }
+    if defaults.https_listener.enabled {
+        listeners.push(Value::Mapping(gen_https_443_sni_listener(
+            defaults, log, tracing, domains, policies, cert_sets,
+        )));
+    }
 
     for internal in &listeners_spec.internal_http_listeners {
         listeners.push(Value::Mapping(gen_internal_http_listener(
-            defaults, log, internal,
+            defaults, log, tracing, internal,
         )));
     }
 
     Value::Sequence(listeners)
 }
 
-fn gen_http_80_listener(defaults: &DefaultsSpec, log: &AccessLogSpec) -> Mapping {
+fn gen_http_80_listener(
+    defaults: &DefaultsSpec,
+    log: &AccessLogSpec,
+    tracing: Option<&TracingSpec>,
+) -> Mapping {
     let mut listener = Mapping::new();
     listener.insert(s("name"), s("http_listener"));
-    listener.insert(s("address"), socket_addr("TCP", "0.0.0.0", 80));
+    insert_builtin_addresses(&mut listener, &defaults.http_listener, defaults.http_port());
 
     let hcm = http_connection_manager(
         "external_http",
@@ -77,9 +450,11 @@ fn gen_http_80_listener(defaults: &DefaultsSpec, log: &AccessLogSpec) -> Mapping
                 None,
             )],
             None,
+            Mapping::new(),
         ),
         vec![http_filter_router()],
         None,
+        tracing.map(|t| tracing_config(t, None)),
     );
 
     let filter_chain = filter_chain_http(hcm);
@@ -87,18 +462,25 @@ fn gen_http_80_listener(defaults: &DefaultsSpec, log: &AccessLogSpec) -> Mapping
         s("filter_chains"),
         Value::Sequence(vec![Value::Mapping(filter_chain)]),
     );
+    apply_listener_limits(&mut listener, &defaults.listener_limits, "http_listener");
     listener
 }
 
 fn gen_https_443_sni_listener(
     defaults: &DefaultsSpec,
     log: &AccessLogSpec,
+    tracing: Option<&TracingSpec>,
     domains: &[DomainSpec],
     policies: &PoliciesSpec,
+    cert_sets: &[CertSetSpec],
 ) -> Mapping {
     let mut listener = Mapping::new();
     listener.insert(s("name"), s("https_sni_listener"));
-    listener.insert(s("address"), socket_addr("TCP", "0.0.0.0", 443));
+    insert_builtin_addresses(
+        &mut listener,
+        &defaults.https_listener,
+        defaults.https_port(),
+    );
 
     // tls_inspector
     listener.insert(
@@ -128,23 +510,37 @@ fn gen_https_443_sni_listener(
         fc.insert(s("filter_chain_match"), {
             let mut m = Mapping::new();
             m.insert(s("server_names"), Value::Sequence(vec![s(&d.domain)]));
+            if let Some(policy) = d
+                .access
+                .as_ref()
+                .and_then(|name| policies.access.get(name))
+                .filter(|p| p.enforce_l4)
+            {
+                m.insert(
+                    s("source_prefix_ranges"),
+                    Value::Sequence(policy.source_cidrs.iter().map(|c| cidr_range(c)).collect()),
+                );
+            }
             Value::Mapping(m)
         });
 
         // transport_socket TLS
-        fc.insert(s("transport_socket"), downstream_tls_socket(tls));
+        fc.insert(
+            s("transport_socket"),
+            downstream_tls_socket(tls, &defaults.tls, cert_sets),
+        );
 
         // HCM routes for this domain
-        let mut routes = Vec::new();
-        let any_route_uses_rl = d.routes.iter().any(|r| {
-            r.per_filter_config
-                .as_ref()
-                .and_then(|p| p.local_ratelimit.as_ref())
-                .is_some()
-        });
+        let routes = d
+            .routes
+            .iter()
+            .map(|r| route_from_spec(r, defaults, policies, d.local_ratelimit.as_deref()))
+            .collect();
 
-        for r in &d.routes {
-            routes.push(route_from_spec(r, defaults, policies));
+        let mut vhost_typed = Mapping::new();
+        if let Some(name) = &d.cors {
+            let policy = policies.cors.get(name).expect("validated");
+            vhost_typed.insert(s("envoy.filters.http.cors"), cors_policy(policy));
         }
 
         let rc = route_config_single_vhost(
@@ -153,39 +549,20 @@ fn gen_https_443_sni_listener(
             vec![d.domain.as_str()],
             routes,
             None,
+            vhost_typed,
         );
-
-        // Filters:
-        // - If any route references local_ratelimit policy, include local_ratelimit filter
-        // - Include any extra filters configured for this domain
-        // - Always include router
-        let mut http_filters = Vec::new();
-        if any_route_uses_rl {
-            let stat_prefix = d
-                .http_connection_manager
-                .as_ref()
-                .and_then(|hcm| hcm.local_ratelimit_stat_prefix.as_deref())
-                .unwrap_or("default_local_ratelimit");
-            http_filters.push(http_filter_local_ratelimit_default(stat_prefix));
-        }
-        if let Some(hcm) = d.http_connection_manager.as_ref() {
-            for filter in &hcm.extra_http_filters {
-                match filter {
-                    HttpFilterSpec::GrpcWeb => http_filters.push(http_filter_grpc_web()),
-                }
-            }
-        }
-        http_filters.push(http_filter_router());
+        let http_filters = http_filters_for_domain(d, policies);
 
         let hcm = http_connection_manager_with_domain(
             &format!("{}_https", sanitize_name(&d.domain)),
-            log,
+            d.access_log.as_ref().unwrap_or(log),
             rc,
             http_filters,
             d.http_connection_manager.as_ref(),
             d.normalize_path,
             d.merge_slashes,
             d.aws_signing.as_ref(),
+            tracing.map(|t| tracing_config(t, d.tracing.as_ref())),
         );
 
         fc.insert(
@@ -218,12 +595,18 @@ fn gen_https_443_sni_listener(
     }));
 
     listener.insert(s("filter_chains"), Value::Sequence(filter_chains));
+    apply_listener_limits(
+        &mut listener,
+        &defaults.listener_limits,
+        "https_sni_listener",
+    );
     listener
 }
 
 fn gen_internal_http_listener(
     defaults: &DefaultsSpec,
     log: &AccessLogSpec,
+    tracing: Option<&TracingSpec>,
     internal: &InternalHttpListenerSpec,
 ) -> Mapping {
     let mut listener = Mapping::new();
@@ -250,14 +633,16 @@ fn gen_internal_http_listener(
         internal.domains.iter().map(String::as_str).collect(),
         routes,
         Some(&internal.request_headers_to_add),
+        Mapping::new(),
     );
 
     let hcm = http_connection_manager(
         &internal.stat_prefix,
-        log,
+        internal.access_log.as_ref().unwrap_or(log),
         rc,
         vec![http_filter_router()],
         None,
+        tracing.map(|t| tracing_config(t, None)),
     );
 
     let filter_chain = filter_chain_http(hcm);
@@ -265,12 +650,99 @@ fn gen_internal_http_listener(
         s("filter_chains"),
         Value::Sequence(vec![Value::Mapping(filter_chain)]),
     );
+    let limits = internal
+        .limits
+        .as_ref()
+        .unwrap_or(&defaults.listener_limits);
+    apply_listener_limits(&mut listener, limits, &internal.stat_prefix);
     listener
 }
 
-fn route_from_spec(r: &RouteSpec, defaults: &DefaultsSpec, policies: &PoliciesSpec) -> Value {
+/// HTTP filter chain for a terminated domain. Order matters: CORS answers
+/// preflights before rate limiting, and the router is always last.
+fn http_filters_for_domain(d: &DomainSpec, policies: &PoliciesSpec) -> Vec<Value> {
+    let mut http_filters = Vec::new();
+    if d.cors.is_some() || d.routes.iter().any(|r| r.cors.is_some()) {
+        http_filters.push(http_filter_cors());
+    }
+    let any_route_uses_rl = d.routes.iter().any(|r| {
+        r.per_filter_config
+            .as_ref()
+            .and_then(|p| p.local_ratelimit.as_ref())
+            .is_some()
+    });
+    if any_route_uses_rl || d.local_ratelimit.is_some() {
+        // The HCM-level filter carries the domain's default bucket, if any;
+        // routes with their own bucket override it per route
+        let bucket = d
+            .local_ratelimit
+            .as_ref()
+            .map(|key| (key, policies.local_ratelimits.get(key).expect("validated")));
+        let stat_prefix = d
+            .http_connection_manager
+            .as_ref()
+            .and_then(|hcm| hcm.local_ratelimit_stat_prefix.clone())
+            .or_else(|| {
+                bucket.map(|(key, tb)| tb.stat_prefix.clone().unwrap_or(format!("rl_{}", key)))
+            })
+            .unwrap_or_else(|| "default_local_ratelimit".to_string());
+        http_filters.push(http_filter_local_ratelimit_default(
+            &stat_prefix,
+            bucket.map(|(_, tb)| tb),
+        ));
+    }
+    // Global limits come after the local bucket, which sheds load without a
+    // round trip to the rate limit service
+    let any_route_uses_global_rl = d.routes.iter().any(|r| {
+        r.per_filter_config
+            .as_ref()
+            .is_some_and(|p| !p.global_ratelimits.is_empty())
+    });
+    if any_route_uses_global_rl {
+        let service = policies.ratelimit_service.as_ref().expect("validated");
+        http_filters.push(http_filter_ratelimit(service));
+    }
+    if d.access.is_some() || d.routes.iter().any(|r| r.access.is_some()) {
+        http_filters.push(http_filter_rbac(d, policies));
+    }
+    if let Some(jwt) = http_filter_jwt_authn(d, policies) {
+        http_filters.push(jwt);
+    }
+    if let Some(name) = &d.ext_authz {
+        let policy = policies.ext_authz.get(name).expect("validated");
+        http_filters.push(http_filter_ext_authz(policy));
+    }
+    if let Some(hcm) = d.http_connection_manager.as_ref() {
+        for filter in &hcm.extra_http_filters {
+            match filter {
+                HttpFilterSpec::GrpcWeb => http_filters.push(http_filter_grpc_web()),
+                HttpFilterSpec::GrpcStats => http_filters.push(http_filter_grpc_stats()),
+            }
+        }
+    }
+    // Compressors run on the response path, so they sit closest to the router
+    for name in &d.compression {
+        let policy = policies.compression.get(name).expect("validated");
+        http_filters.push(http_filter_compressor(name, policy));
+    }
+    http_filters.push(http_filter_router());
+    http_filters
+}
+
+fn route_from_spec(
+    r: &RouteSpec,
+    defaults: &DefaultsSpec,
+    policies: &PoliciesSpec,
+    domain_ratelimit: Option<&str>,
+) -> Value {
     let mut route = Mapping::new();
-    route.insert(s("match"), match_to_value(&r.m));
+    if r.connect {
+        let mut m = Mapping::new();
+        m.insert(s("connect_matcher"), Value::Mapping(Mapping::new()));
+        route.insert(s("match"), Value::Mapping(m));
+    } else {
+        route.insert(s("match"), match_to_value(&r.m));
+    }
 
     // Check if this is a direct response route
     if let Some(dr) = &r.direct_response {
@@ -303,13 +775,74 @@ fn route_from_spec(r: &RouteSpec, defaults: &DefaultsSpec, policies: &PoliciesSp
     if let Some(rewrite) = &r.prefix_rewrite {
         route_action.insert(s("prefix_rewrite"), s(rewrite));
     }
+    if r.max_grpc_timeout.is_some() || r.grpc_timeout_header_offset.is_some() {
+        let mut msd = Mapping::new();
+        if let Some(max) = &r.max_grpc_timeout {
+            msd.insert(s("grpc_timeout_header_max"), s(max));
+        }
+        if let Some(offset) = &r.grpc_timeout_header_offset {
+            msd.insert(s("grpc_timeout_header_offset"), s(offset));
+        }
+        route_action.insert(s("max_stream_duration"), Value::Mapping(msd));
+    }
+    if !r.hash_policy.is_empty() {
+        route_action.insert(
+            s("hash_policy"),
+            Value::Sequence(r.hash_policy.iter().map(hash_policy).collect()),
+        );
+    }
+    let mut upgrades = Vec::new();
+    if r.websocket {
+        upgrades.push(upgrade_config("websocket", false));
+    }
+    if r.connect {
+        upgrades.push(upgrade_config("CONNECT", true));
+    }
+    if !upgrades.is_empty() {
+        route_action.insert(s("upgrade_configs"), Value::Sequence(upgrades));
+    }
+    let mut rate_limits = Vec::new();
+    if let Some(pfc) = &r.per_filter_config {
+        for name in &pfc.global_ratelimits {
+            let policy = policies.global_ratelimits.get(name).expect("validated");
+            let mut m = Mapping::new();
+            m.insert(
+                s("actions"),
+                Value::Sequence(policy.actions.iter().map(rate_limit_action).collect()),
+            );
+            rate_limits.push(Value::Mapping(m));
+        }
+    }
+    // Local descriptor buckets need actions producing their descriptors. They
+    // run in their own stage so the global rate limit filter ignores them.
+    let local_bucket = r
+        .per_filter_config
+        .as_ref()
+        .and_then(|p| p.local_ratelimit.as_deref())
+        .or(domain_ratelimit)
+        .map(|key| policies.local_ratelimits.get(key).expect("validated"));
+    if let Some(tb) = local_bucket {
+        let mut seen = HashSet::new();
+        for d in tb.descriptors.iter().filter(|d| seen.insert(d.key())) {
+            let mut m = Mapping::new();
+            m.insert(s("stage"), n(LOCAL_RATELIMIT_STAGE));
+            m.insert(
+                s("actions"),
+                Value::Sequence(vec![local_descriptor_action(d)]),
+            );
+            rate_limits.push(Value::Mapping(m));
+        }
+    }
+    if !rate_limits.is_empty() {
+        route_action.insert(s("rate_limits"), Value::Sequence(rate_limits));
+    }
     route.insert(s("route"), Value::Mapping(route_action));
 
-    // per-route typed_per_filter_config (local_ratelimit)
+    // per-route typed_per_filter_config (local_ratelimit, cors)
+    let mut typed = Mapping::new();
     if let Some(pfc) = &r.per_filter_config {
         if let Some(key) = &pfc.local_ratelimit {
             let tb = policies.local_ratelimits.get(key).expect("validated");
-            let mut typed = Mapping::new();
             let stat_prefix = tb
                 .stat_prefix
                 .as_deref()
@@ -317,27 +850,82 @@ fn route_from_spec(r: &RouteSpec, defaults: &DefaultsSpec, policies: &PoliciesSp
                 .unwrap_or_else(|| format!("rl_{}", key));
             typed.insert(
                 s("envoy.filters.http.local_ratelimit"),
-                Value::Mapping({
-                    let mut cfg = Mapping::new();
-                    cfg.insert(s("@type"), s("type.googleapis.com/envoy.extensions.filters.http.local_ratelimit.v3.LocalRateLimit"));
-                    cfg.insert(s("stat_prefix"), s(stat_prefix));
-                    cfg.insert(s("token_bucket"), Value::Mapping({
-                        let mut t = Mapping::new();
-                        t.insert(s("max_tokens"), n(tb.max_tokens));
-                        t.insert(s("tokens_per_fill"), n(tb.tokens_per_fill));
-                        t.insert(s("fill_interval"), s(&tb.fill_interval));
-                        t
-                    }));
-                    cfg
-                }),
+                Value::Mapping(local_ratelimit_config(&stat_prefix, Some(tb))),
             );
-            route.insert(s("typed_per_filter_config"), Value::Mapping(typed));
         }
     }
+    if let Some(name) = &r.cors {
+        let policy = policies.cors.get(name).expect("validated");
+        typed.insert(s("envoy.filters.http.cors"), cors_policy(policy));
+    }
+    if let Some(jwt) = &r.jwt {
+        let mut cfg = Mapping::new();
+        cfg.insert(
+            s("@type"),
+            s("type.googleapis.com/envoy.extensions.filters.http.jwt_authn.v3.PerRouteConfig"),
+        );
+        if jwt.disabled {
+            cfg.insert(s("disabled"), b(true));
+        } else {
+            cfg.insert(s("requirement_name"), s(jwt_requirement_name(jwt)));
+        }
+        typed.insert(s("envoy.filters.http.jwt_authn"), Value::Mapping(cfg));
+    }
+    if r.ext_authz.as_ref().is_some_and(|e| e.disabled) {
+        let mut cfg = Mapping::new();
+        cfg.insert(
+            s("@type"),
+            s("type.googleapis.com/envoy.extensions.filters.http.ext_authz.v3.ExtAuthzPerRoute"),
+        );
+        cfg.insert(s("disabled"), b(true));
+        typed.insert(s("envoy.filters.http.ext_authz"), Value::Mapping(cfg));
+    }
+    if let Some(name) = &r.access {
+        let policy = policies.access.get(name).expect("validated");
+        let mut rbac = Mapping::new();
+        rbac.insert(s("rules"), rbac_rules(name, policy));
+        let mut cfg = Mapping::new();
+        cfg.insert(
+            s("@type"),
+            s("type.googleapis.com/envoy.extensions.filters.http.rbac.v3.RBACPerRoute"),
+        );
+        cfg.insert(s("rbac"), Value::Mapping(rbac));
+        typed.insert(s("envoy.filters.http.rbac"), Value::Mapping(cfg));
+    }
+    if !typed.is_empty() {
+        route.insert(s("typed_per_filter_config"), Value::Mapping(typed));
+    }
 
     Value::Mapping(route)
 }
 
+fn hash_policy(h: &HashPolicySpec) -> Value {
+    let mut m = Mapping::new();
+    if let Some(name) = &h.header {
+        let mut hdr = Mapping::new();
+        hdr.insert(s("header_name"), s(name));
+        m.insert(s("header"), Value::Mapping(hdr));
+    } else if let Some(c) = &h.cookie {
+        let mut cookie = Mapping::new();
+        cookie.insert(s("name"), s(&c.name));
+        if let Some(ttl) = &c.ttl {
+            cookie.insert(s("ttl"), s(ttl));
+        }
+        if let Some(path) = &c.path {
+            cookie.insert(s("path"), s(path));
+        }
+        m.insert(s("cookie"), Value::Mapping(cookie));
+    } else if h.source_ip {
+        let mut ip = Mapping::new();
+        ip.insert(s("source_ip"), b(true));
+        m.insert(s("connection_properties"), Value::Mapping(ip));
+    }
+    if h.terminal {
+        m.insert(s("terminal"), b(true));
+    }
+    Value::Mapping(m)
+}
+
 fn match_to_value(m: &MatchSpec) -> Value {
     let mut mm = Mapping::new();
     if let Some(prefix) = &m.prefix {
@@ -362,6 +950,9 @@ fn match_to_value(m: &MatchSpec) -> Value {
             .collect();
         mm.insert(s("headers"), Value::Sequence(headers));
     }
+    if m.grpc {
+        mm.insert(s("grpc"), Value::Mapping(Mapping::new()));
+    }
 
     Value::Mapping(mm)
 }
@@ -383,44 +974,198 @@ fn gen_cluster(u: &UpstreamSpec) -> Mapping {
     let mut m = Mapping::new();
     m.insert(s("name"), s(&u.name));
     m.insert(s("connect_timeout"), s(&u.connect_timeout));
-    m.insert(s("type"), s(&u.r#type));
-    m.insert(s("lb_policy"), s(&u.lb_policy));
+    m.insert(s("type"), s(u.r#type.as_str()));
+    if let Some(f) = u.dns_lookup_family {
+        m.insert(s("dns_lookup_family"), s(f.as_str()));
+    }
+    if let Some(rate) = &u.dns_refresh_rate {
+        m.insert(s("dns_refresh_rate"), s(rate));
+    }
+    if let Some(v) = u.respect_dns_ttl {
+        m.insert(s("respect_dns_ttl"), b(v));
+    }
+    m.insert(s("lb_policy"), s(u.lb_policy.as_str()));
+    if let Some(lr) = &u.least_request {
+        let mut c = Mapping::new();
+        if let Some(v) = lr.choice_count {
+            c.insert(s("choice_count"), n(v));
+        }
+        m.insert(s("least_request_lb_config"), Value::Mapping(c));
+    }
+    if let Some(rh) = &u.ring_hash {
+        let mut c = Mapping::new();
+        if let Some(v) = rh.minimum_ring_size {
+            c.insert(s("minimum_ring_size"), n(v));
+        }
+        if let Some(v) = rh.maximum_ring_size {
+            c.insert(s("maximum_ring_size"), n(v));
+        }
+        m.insert(s("ring_hash_lb_config"), Value::Mapping(c));
+    }
+    if let Some(mg) = &u.maglev {
+        let mut c = Mapping::new();
+        if let Some(v) = mg.table_size {
+            c.insert(s("table_size"), n(v));
+        }
+        m.insert(s("maglev_lb_config"), Value::Mapping(c));
+    }
 
-    if u.http2 {
+    if u.preset == Some(UpstreamPreset::Grpc) {
+        m.insert(s("http2_protocol_options"), grpc_http2_options());
+    } else if u.http2 {
         m.insert(s("http2_protocol_options"), Value::Mapping(Mapping::new()));
     }
+    if let Some(od) = &u.outlier_detection {
+        m.insert(s("outlier_detection"), outlier_detection(od));
+    }
+    if let Some(cb) = &u.circuit_breakers {
+        m.insert(s("circuit_breakers"), circuit_breakers(cb));
+    }
+    if let Some(tls) = &u.tls {
+        m.insert(s("transport_socket"), upstream_tls_socket(tls, u));
+    }
+
+    // EDS clusters get their endpoints from the discovery file instead
+    if u.r#type == ClusterType::Eds {
+        let path = u.eds_config_path.as_deref().expect("validated");
+        let mut source = Mapping::new();
+        source.insert(
+            s("path_config_source"),
+            Value::Mapping({
+                let mut p = Mapping::new();
+                p.insert(s("path"), s(path));
+                p
+            }),
+        );
+        source.insert(s("resource_api_version"), s("V3"));
+        let mut eds = Mapping::new();
+        eds.insert(s("eds_config"), Value::Mapping(source));
+        m.insert(s("eds_cluster_config"), Value::Mapping(eds));
+        return m;
+    }
 
     let mut load_assignment = Mapping::new();
     load_assignment.insert(s("cluster_name"), s(&u.name));
 
-    let mut lb_eps = Vec::new();
-    for ep in &u.endpoints {
+    let mut groups = Vec::new();
+    if !u.endpoints.is_empty() {
         let mut e = Mapping::new();
-        e.insert(
-            s("endpoint"),
-            Value::Mapping({
-                let mut endpoint = Mapping::new();
-                endpoint.insert(s("address"), socket_addr("TCP", &ep.address, ep.port));
-                endpoint
-            }),
-        );
-        lb_eps.push(Value::Mapping(e));
+        e.insert(s("lb_endpoints"), lb_endpoints(&u.endpoints));
+        groups.push(Value::Mapping(e));
+    }
+    for l in &u.localities {
+        let mut e = Mapping::new();
+        let mut locality = Mapping::new();
+        if let Some(v) = &l.region {
+            locality.insert(s("region"), s(v));
+        }
+        if let Some(v) = &l.zone {
+            locality.insert(s("zone"), s(v));
+        }
+        if let Some(v) = &l.sub_zone {
+            locality.insert(s("sub_zone"), s(v));
+        }
+        if !locality.is_empty() {
+            e.insert(s("locality"), Value::Mapping(locality));
+        }
+        e.insert(s("lb_endpoints"), lb_endpoints(&l.endpoints));
+        if let Some(w) = l.load_balancing_weight {
+            e.insert(s("load_balancing_weight"), n(w));
+        }
+        if l.priority > 0 {
+            e.insert(s("priority"), n(l.priority));
+        }
+        groups.push(Value::Mapping(e));
     }
+    load_assignment.insert(s("endpoints"), Value::Sequence(groups));
 
-    load_assignment.insert(
-        s("endpoints"),
-        Value::Sequence(vec![Value::Mapping({
-            let mut e = Mapping::new();
-            e.insert(s("lb_endpoints"), Value::Sequence(lb_eps));
-            e
-        })]),
-    );
+    // Locality weights are ignored unless the cluster opts in
+    if u.localities
+        .iter()
+        .any(|l| l.load_balancing_weight.is_some())
+    {
+        let mut common = Mapping::new();
+        common.insert(
+            s("locality_weighted_lb_config"),
+            Value::Mapping(Mapping::new()),
+        );
+        m.insert(s("common_lb_config"), Value::Mapping(common));
+    }
 
     m.insert(s("load_assignment"), Value::Mapping(load_assignment));
     m
 }
 
-/* ---------------- building blocks ---------------- */
+fn lb_endpoints(endpoints: &[Endpoint]) -> Value {
+    Value::Sequence(
+        endpoints
+            .iter()
+            .map(|ep| {
+                let mut endpoint = Mapping::new();
+                endpoint.insert(s("address"), socket_addr("TCP", &ep.address, ep.port));
+                if let Some(h) = &ep.hostname {
+                    endpoint.insert(s("hostname"), s(h));
+                }
+                let mut e = Mapping::new();
+                e.insert(s("endpoint"), Value::Mapping(endpoint));
+                if let Some(w) = ep.weight {
+                    e.insert(s("load_balancing_weight"), n(w));
+                }
+                Value::Mapping(e)
+            })
+            .collect(),
+    )
+}
+
+fn outlier_detection(od: &OutlierDetectionSpec) -> Value {
+    let mut m = Mapping::new();
+    if let Some(v) = od.consecutive_5xx {
+        m.insert(s("consecutive_5xx"), n(v));
+    }
+    if let Some(v) = od.consecutive_gateway_failure {
+        m.insert(s("consecutive_gateway_failure"), n(v));
+        // Gateway failure tracking is opt-in on the Envoy side
+        m.insert(s("enforcing_consecutive_gateway_failure"), n(100u32));
+    }
+    if let Some(v) = &od.interval {
+        m.insert(s("interval"), s(v));
+    }
+    if let Some(v) = &od.base_ejection_time {
+        m.insert(s("base_ejection_time"), s(v));
+    }
+    if let Some(v) = od.max_ejection_percent {
+        m.insert(s("max_ejection_percent"), n(v));
+    }
+    Value::Mapping(m)
+}
+
+fn circuit_breakers(cb: &CircuitBreakersSpec) -> Value {
+    let mut thresholds = Vec::new();
+    for (priority, t) in [("DEFAULT", &cb.default), ("HIGH", &cb.high)] {
+        let Some(t) = t else { continue };
+        let mut m = Mapping::new();
+        m.insert(s("priority"), s(priority));
+        if let Some(v) = t.max_connections {
+            m.insert(s("max_connections"), n(v));
+        }
+        if let Some(v) = t.max_pending_requests {
+            m.insert(s("max_pending_requests"), n(v));
+        }
+        if let Some(v) = t.max_requests {
+            m.insert(s("max_requests"), n(v));
+        }
+        if let Some(v) = t.max_retries {
+            m.insert(s("max_retries"), n(v));
+        }
+        thresholds.push(Value::Mapping(m));
+    }
+
+    let mut m = Mapping::new();
+    m.insert(s("thresholds"), Value::Sequence(thresholds));
+    Value::Mapping(m)
+}
+
+/* ---------------- building blocks ---------------- */
 
 fn http_connection_manager(
     stat_prefix: &str,
@@ -428,6 +1173,7 @@ fn http_connection_manager(
     route_config: Value,
     http_filters: Vec<Value>,
     overrides: Option<&HttpConnectionManagerSpec>,
+    tracing: Option<Value>,
 ) -> Value {
     http_connection_manager_with_domain(
         stat_prefix,
@@ -438,6 +1184,7 @@ fn http_connection_manager(
         None,
         None,
         None,
+        tracing,
     )
 }
 
@@ -451,6 +1198,7 @@ fn http_connection_manager_with_domain(
     domain_normalize_path: Option<bool>,
     domain_merge_slashes: Option<bool>,
     aws_signing: Option<&AwsSigningSpec>,
+    tracing: Option<Value>,
 ) -> Value {
     let mut hcm = Mapping::new();
     let stat_prefix = overrides
@@ -480,7 +1228,34 @@ fn http_connection_manager_with_domain(
     if let Some(stream_idle_timeout) = overrides.and_then(|o| o.stream_idle_timeout.as_deref()) {
         hcm.insert(s("stream_idle_timeout"), s(stream_idle_timeout));
     }
-    hcm.insert(s("access_log"), stdout_access_log(log));
+    if let Some(fccd) = overrides.and_then(|o| o.forward_client_cert_details) {
+        hcm.insert(s("forward_client_cert_details"), s(fccd.as_str()));
+    }
+    if let Some(details) = overrides.and_then(|o| o.set_current_client_cert_details.as_ref()) {
+        let mut m = Mapping::new();
+        for (key, enabled) in [
+            ("subject", details.subject),
+            ("cert", details.cert),
+            ("chain", details.chain),
+            ("dns", details.dns),
+            ("uri", details.uri),
+        ] {
+            if enabled {
+                m.insert(s(key), b(true));
+            }
+        }
+        hcm.insert(s("set_current_client_cert_details"), Value::Mapping(m));
+    }
+    if overrides.is_some_and(|o| o.websocket) {
+        hcm.insert(
+            s("upgrade_configs"),
+            Value::Sequence(vec![upgrade_config("websocket", false)]),
+        );
+    }
+    hcm.insert(s("access_log"), access_log(log, stat_prefix));
+    if let Some(tracing) = tracing {
+        hcm.insert(s("tracing"), tracing);
+    }
     hcm.insert(s("route_config"), route_config);
 
     // If AWS signing is enabled, we need to use upstream_http_filters in the router
@@ -517,6 +1292,7 @@ fn route_config_single_vhost(
     domains: Vec<&str>,
     routes: Vec<Value>,
     request_headers_to_add: Option<&[HeaderValueOption]>,
+    typed_per_filter_config: Mapping,
 ) -> Value {
     let mut rc = Mapping::new();
     rc.insert(s("name"), s(name.into()));
@@ -538,12 +1314,29 @@ fn route_config_single_vhost(
                 }
             }
             vh.insert(s("routes"), Value::Sequence(routes));
+            if !typed_per_filter_config.is_empty() {
+                vh.insert(
+                    s("typed_per_filter_config"),
+                    Value::Mapping(typed_per_filter_config),
+                );
+            }
             vh
         })]),
     );
     Value::Mapping(rc)
 }
 
+/// `terminate` adds an empty connect_config, so Envoy ends the CONNECT
+/// request itself and forwards the payload as raw TCP.
+fn upgrade_config(upgrade_type: &str, terminate: bool) -> Value {
+    let mut m = Mapping::new();
+    m.insert(s("upgrade_type"), s(upgrade_type));
+    if terminate {
+        m.insert(s("connect_config"), Value::Mapping(Mapping::new()));
+    }
+    Value::Mapping(m)
+}
+
 fn route_prefix_to_cluster(
     prefix: &str,
     cluster: &str,
@@ -672,20 +1465,599 @@ fn http_filter_router_with_aws_signing(signing: &AwsSigningSpec) -> Value {
     })
 }
 
-fn http_filter_local_ratelimit_default(stat_prefix: &str) -> Value {
+fn http_filter_local_ratelimit_default(stat_prefix: &str, bucket: Option<&TokenBucket>) -> Value {
+    let mut f = Mapping::new();
+    f.insert(s("name"), s("envoy.filters.http.local_ratelimit"));
+    f.insert(
+        s("typed_config"),
+        Value::Mapping(local_ratelimit_config(stat_prefix, bucket)),
+    );
+    Value::Mapping(f)
+}
+
+/// Stage shared by local descriptor buckets and the route actions feeding
+/// them; global rate limits use the default stage 0.
+const LOCAL_RATELIMIT_STAGE: u32 = 1;
+
+/// LocalRateLimit config. Without a bucket the filter is only a placeholder
+/// for per-route configs.
+fn local_ratelimit_config(stat_prefix: &str, bucket: Option<&TokenBucket>) -> Mapping {
+    let mut cfg = Mapping::new();
+    cfg.insert(
+        s("@type"),
+        s("type.googleapis.com/envoy.extensions.filters.http.local_ratelimit.v3.LocalRateLimit"),
+    );
+    cfg.insert(s("stat_prefix"), s(stat_prefix));
+    let Some(tb) = bucket else {
+        return cfg;
+    };
+    cfg.insert(
+        s("token_bucket"),
+        token_bucket(tb.max_tokens, tb.tokens_per_fill, &tb.fill_interval),
+    );
+    // Envoy evaluates and enforces 0% of requests when these are unset
+    cfg.insert(
+        s("filter_enabled"),
+        runtime_percent(
+            &format!("{stat_prefix}_enabled"),
+            tb.filter_enabled.unwrap_or(100),
+        ),
+    );
+    cfg.insert(
+        s("filter_enforced"),
+        runtime_percent(
+            &format!("{stat_prefix}_enforced"),
+            tb.filter_enforced.unwrap_or(100),
+        ),
+    );
+    if let Some(code) = tb.status {
+        let mut status = Mapping::new();
+        status.insert(s("code"), n(code));
+        cfg.insert(s("status"), Value::Mapping(status));
+    }
+    if !tb.response_headers_to_add.is_empty() {
+        cfg.insert(
+            s("response_headers_to_add"),
+            Value::Sequence(
+                tb.response_headers_to_add
+                    .iter()
+                    .map(header_value_option)
+                    .collect(),
+            ),
+        );
+    }
+    if !tb.descriptors.is_empty() {
+        let descriptors = tb
+            .descriptors
+            .iter()
+            .map(|d| {
+                let mut entry = Mapping::new();
+                entry.insert(s("key"), s(d.key()));
+                entry.insert(s("value"), s(&d.value));
+                let mut m = Mapping::new();
+                m.insert(s("entries"), Value::Sequence(vec![Value::Mapping(entry)]));
+                m.insert(
+                    s("token_bucket"),
+                    token_bucket(d.max_tokens, d.tokens_per_fill, &d.fill_interval),
+                );
+                Value::Mapping(m)
+            })
+            .collect();
+        cfg.insert(s("descriptors"), Value::Sequence(descriptors));
+        cfg.insert(s("stage"), n(LOCAL_RATELIMIT_STAGE));
+    }
+    cfg
+}
+
+fn token_bucket(max_tokens: u32, tokens_per_fill: u32, fill_interval: &str) -> Value {
+    let mut t = Mapping::new();
+    t.insert(s("max_tokens"), n(max_tokens));
+    t.insert(s("tokens_per_fill"), n(tokens_per_fill));
+    t.insert(s("fill_interval"), s(fill_interval));
+    Value::Mapping(t)
+}
+
+fn runtime_percent(runtime_key: &str, percent: u32) -> Value {
+    let mut value = Mapping::new();
+    value.insert(s("numerator"), n(percent));
+    value.insert(s("denominator"), s("HUNDRED"));
+    let mut m = Mapping::new();
+    m.insert(s("runtime_key"), s(runtime_key));
+    m.insert(s("default_value"), Value::Mapping(value));
+    Value::Mapping(m)
+}
+
+fn local_descriptor_action(d: &LocalRateLimitDescriptorSpec) -> Value {
+    let mut m = Mapping::new();
+    match &d.header {
+        Some(header) => {
+            let mut rh = Mapping::new();
+            rh.insert(s("header_name"), s(header));
+            rh.insert(s("descriptor_key"), s(header));
+            m.insert(s("request_headers"), Value::Mapping(rh));
+        }
+        None => {
+            m.insert(s("remote_address"), Value::Mapping(Mapping::new()));
+        }
+    }
+    Value::Mapping(m)
+}
+
+fn http_filter_ratelimit(service: &RateLimitServiceSpec) -> Value {
+    let mut envoy_grpc = Mapping::new();
+    envoy_grpc.insert(s("cluster_name"), s(&service.upstream));
+    let mut grpc_service = Mapping::new();
+    grpc_service.insert(s("envoy_grpc"), Value::Mapping(envoy_grpc));
+    let mut rls = Mapping::new();
+    rls.insert(s("grpc_service"), Value::Mapping(grpc_service));
+    rls.insert(s("transport_api_version"), s("V3"));
+
+    let mut tc = Mapping::new();
+    tc.insert(
+        s("@type"),
+        s("type.googleapis.com/envoy.extensions.filters.http.ratelimit.v3.RateLimit"),
+    );
+    tc.insert(s("domain"), s(&service.domain));
+    tc.insert(s("timeout"), s(&service.timeout));
+    tc.insert(s("failure_mode_deny"), b(service.failure_mode_deny));
+    tc.insert(s("rate_limit_service"), Value::Mapping(rls));
+
+    let mut f = Mapping::new();
+    f.insert(s("name"), s("envoy.filters.http.ratelimit"));
+    f.insert(s("typed_config"), Value::Mapping(tc));
+    Value::Mapping(f)
+}
+
+fn rate_limit_action(action: &RateLimitActionSpec) -> Value {
+    let mut m = Mapping::new();
+    if action.remote_address {
+        m.insert(s("remote_address"), Value::Mapping(Mapping::new()));
+    } else if let Some(h) = &action.request_headers {
+        let mut rh = Mapping::new();
+        rh.insert(s("header_name"), s(&h.header_name));
+        rh.insert(s("descriptor_key"), s(&h.descriptor_key));
+        if h.skip_if_absent {
+            rh.insert(s("skip_if_absent"), b(true));
+        }
+        m.insert(s("request_headers"), Value::Mapping(rh));
+    } else if let Some(g) = &action.generic_key {
+        let mut gk = Mapping::new();
+        gk.insert(s("descriptor_value"), s(&g.descriptor_value));
+        if let Some(key) = &g.descriptor_key {
+            gk.insert(s("descriptor_key"), s(key));
+        }
+        m.insert(s("generic_key"), Value::Mapping(gk));
+    }
+    Value::Mapping(m)
+}
+
+fn http_filter_cors() -> Value {
     Value::Mapping({
         let mut f = Mapping::new();
-        f.insert(s("name"), s("envoy.filters.http.local_ratelimit"));
-        f.insert(s("typed_config"), Value::Mapping({
-            let mut tc = Mapping::new();
-            tc.insert(s("@type"), s("type.googleapis.com/envoy.extensions.filters.http.local_ratelimit.v3.LocalRateLimit"));
-            tc.insert(s("stat_prefix"), s(stat_prefix));
-            tc
-        }));
+        f.insert(s("name"), s("envoy.filters.http.cors"));
+        f.insert(
+            s("typed_config"),
+            Value::Mapping({
+                let mut tc = Mapping::new();
+                tc.insert(
+                    s("@type"),
+                    s("type.googleapis.com/envoy.extensions.filters.http.cors.v3.Cors"),
+                );
+                tc
+            }),
+        );
         f
     })
 }
 
+/// Per-vhost / per-route CorsPolicy for `typed_per_filter_config`
+fn cors_policy(c: &CorsPolicySpec) -> Value {
+    let mut m = Mapping::new();
+    m.insert(
+        s("@type"),
+        s("type.googleapis.com/envoy.extensions.filters.http.cors.v3.CorsPolicy"),
+    );
+    m.insert(
+        s("allow_origin_string_match"),
+        Value::Sequence(c.allow_origins.iter().map(string_matcher).collect()),
+    );
+    // Envoy takes these lists as comma-separated strings
+    for (key, list) in [
+        ("allow_methods", &c.allow_methods),
+        ("allow_headers", &c.allow_headers),
+        ("expose_headers", &c.expose_headers),
+    ] {
+        if !list.is_empty() {
+            m.insert(s(key), s(list.join(",")));
+        }
+    }
+    if let Some(age) = c.max_age {
+        m.insert(s("max_age"), s(age.to_string()));
+    }
+    if let Some(v) = c.allow_credentials {
+        m.insert(s("allow_credentials"), b(v));
+    }
+    Value::Mapping(m)
+}
+
+/// jwt_authn filter holding the providers this domain uses. The domain
+/// requirement becomes filter `rules`; route requirements are looked up by
+/// name in `requirement_map` from their per-route config.
+fn http_filter_jwt_authn(d: &DomainSpec, policies: &PoliciesSpec) -> Option<Value> {
+    let requirements: Vec<&JwtRequirementSpec> = d
+        .jwt
+        .iter()
+        .chain(d.routes.iter().filter_map(|r| r.jwt.as_ref()))
+        .filter(|j| !j.disabled && j.provider.is_some())
+        .collect();
+    if requirements.is_empty() {
+        return None;
+    }
+
+    let mut providers = Mapping::new();
+    for (name, p) in &policies.jwt_providers {
+        if requirements
+            .iter()
+            .any(|r| r.provider.as_deref() == Some(name.as_str()))
+        {
+            providers.insert(s(name), jwt_provider(p));
+        }
+    }
+
+    let mut tc = Mapping::new();
+    tc.insert(
+        s("@type"),
+        s("type.googleapis.com/envoy.extensions.filters.http.jwt_authn.v3.JwtAuthentication"),
+    );
+    tc.insert(s("providers"), Value::Mapping(providers));
+
+    if let Some(jwt) = d.jwt.as_ref().filter(|j| j.provider.is_some()) {
+        let mut rules: Vec<Value> = jwt
+            .bypass_paths
+            .iter()
+            .map(|p| {
+                let mut m = Mapping::new();
+                m.insert(s("match"), prefix_match(p));
+                Value::Mapping(m)
+            })
+            .collect();
+        let mut rule = Mapping::new();
+        rule.insert(s("match"), prefix_match("/"));
+        rule.insert(s("requires"), jwt_requirement(jwt));
+        rules.push(Value::Mapping(rule));
+        tc.insert(s("rules"), Value::Sequence(rules));
+    }
+
+    let mut requirement_map = Mapping::new();
+    for r in d.routes.iter().filter_map(|r| r.jwt.as_ref()) {
+        if !r.disabled {
+            requirement_map.insert(s(jwt_requirement_name(r)), jwt_requirement(r));
+        }
+    }
+    if !requirement_map.is_empty() {
+        tc.insert(s("requirement_map"), Value::Mapping(requirement_map));
+    }
+
+    let mut f = Mapping::new();
+    f.insert(s("name"), s("envoy.filters.http.jwt_authn"));
+    f.insert(s("typed_config"), Value::Mapping(tc));
+    Some(Value::Mapping(f))
+}
+
+/// RBAC filter enforcing the domain's access policy. Routes override it via
+/// `RBACPerRoute`; without a domain policy the filter only serves those.
+fn http_filter_rbac(d: &DomainSpec, policies: &PoliciesSpec) -> Value {
+    let mut tc = Mapping::new();
+    tc.insert(
+        s("@type"),
+        s("type.googleapis.com/envoy.extensions.filters.http.rbac.v3.RBAC"),
+    );
+    if let Some(name) = &d.access {
+        let policy = policies.access.get(name).expect("validated");
+        tc.insert(s("rules"), rbac_rules(name, policy));
+    }
+    let mut f = Mapping::new();
+    f.insert(s("name"), s("envoy.filters.http.rbac"));
+    f.insert(s("typed_config"), Value::Mapping(tc));
+    Value::Mapping(f)
+}
+
+fn rbac_rules(name: &str, a: &AccessPolicySpec) -> Value {
+    let mut ids = Vec::new();
+    if !a.source_cidrs.is_empty() {
+        ids.push(or_ids(
+            a.source_cidrs.iter().map(|c| remote_ip(c)).collect(),
+        ));
+    }
+    if !a.except_cidrs.is_empty() {
+        let mut not = Mapping::new();
+        not.insert(
+            s("not_id"),
+            or_ids(a.except_cidrs.iter().map(|c| remote_ip(c)).collect()),
+        );
+        ids.push(Value::Mapping(not));
+    }
+    for h in &a.headers {
+        let mut hm = Mapping::new();
+        hm.insert(s("name"), s(&h.name));
+        match &h.exact_match {
+            Some(v) => {
+                let mut sm = Mapping::new();
+                sm.insert(s("exact"), s(v));
+                hm.insert(s("string_match"), Value::Mapping(sm));
+            }
+            None => {
+                hm.insert(s("present_match"), b(true));
+            }
+        }
+        let mut id = Mapping::new();
+        id.insert(s("header"), Value::Mapping(hm));
+        ids.push(Value::Mapping(id));
+    }
+
+    let mut and_ids = Mapping::new();
+    and_ids.insert(s("ids"), Value::Sequence(ids));
+    let mut principal = Mapping::new();
+    principal.insert(s("and_ids"), Value::Mapping(and_ids));
+
+    let mut any = Mapping::new();
+    any.insert(s("any"), b(true));
+    let mut policy = Mapping::new();
+    policy.insert(s("permissions"), Value::Sequence(vec![Value::Mapping(any)]));
+    policy.insert(
+        s("principals"),
+        Value::Sequence(vec![Value::Mapping(principal)]),
+    );
+
+    let mut named = Mapping::new();
+    named.insert(s(name), Value::Mapping(policy));
+    let mut rules = Mapping::new();
+    rules.insert(s("action"), s(a.action.as_str()));
+    rules.insert(s("policies"), Value::Mapping(named));
+    Value::Mapping(rules)
+}
+
+fn or_ids(ids: Vec<Value>) -> Value {
+    let mut or = Mapping::new();
+    or.insert(s("ids"), Value::Sequence(ids));
+    let mut m = Mapping::new();
+    m.insert(s("or_ids"), Value::Mapping(or));
+    Value::Mapping(m)
+}
+
+fn remote_ip(cidr: &str) -> Value {
+    let mut m = Mapping::new();
+    m.insert(s("remote_ip"), cidr_range(cidr));
+    Value::Mapping(m)
+}
+
+/// CidrRange from "10.0.0.0/8"; a bare address covers just that host
+fn cidr_range(cidr: &str) -> Value {
+    let (addr, len) = match cidr.split_once('/') {
+        Some((addr, len)) => (addr, len.parse::<u32>().expect("validated")),
+        None if cidr.contains(':') => (cidr, 128),
+        None => (cidr, 32),
+    };
+    let mut m = Mapping::new();
+    m.insert(s("address_prefix"), s(addr));
+    m.insert(s("prefix_len"), n(len));
+    Value::Mapping(m)
+}
+
+fn http_filter_compressor(name: &str, c: &CompressionPolicySpec) -> Value {
+    let mut library_config = Mapping::new();
+    let (type_url, level_key) = match c.algorithm {
+        CompressionAlgorithm::Gzip => (
+            "type.googleapis.com/envoy.extensions.compression.gzip.compressor.v3.Gzip",
+            "compression_level",
+        ),
+        CompressionAlgorithm::Brotli => (
+            "type.googleapis.com/envoy.extensions.compression.brotli.compressor.v3.Brotli",
+            "quality",
+        ),
+        CompressionAlgorithm::Zstd => (
+            "type.googleapis.com/envoy.extensions.compression.zstd.compressor.v3.Zstd",
+            "compression_level",
+        ),
+    };
+    library_config.insert(s("@type"), s(type_url));
+    if let Some(level) = c.level {
+        // gzip takes an enum, the others a plain number
+        let v = match c.algorithm {
+            CompressionAlgorithm::Gzip => s(format!("COMPRESSION_LEVEL_{}", level)),
+            _ => n(level),
+        };
+        library_config.insert(s(level_key), v);
+    }
+    let mut library = Mapping::new();
+    library.insert(s("name"), s(name));
+    library.insert(s("typed_config"), Value::Mapping(library_config));
+
+    let mut common = Mapping::new();
+    if let Some(len) = c.min_content_length {
+        common.insert(s("min_content_length"), n(len));
+    }
+    if !c.content_types.is_empty() {
+        common.insert(
+            s("content_type"),
+            Value::Sequence(c.content_types.iter().map(s).collect()),
+        );
+    }
+    let mut response = Mapping::new();
+    response.insert(s("common_config"), Value::Mapping(common));
+
+    let mut tc = Mapping::new();
+    tc.insert(
+        s("@type"),
+        s("type.googleapis.com/envoy.extensions.filters.http.compressor.v3.Compressor"),
+    );
+    tc.insert(s("response_direction_config"), Value::Mapping(response));
+    tc.insert(s("compressor_library"), Value::Mapping(library));
+
+    let mut f = Mapping::new();
+    f.insert(
+        s("name"),
+        s(format!("envoy.filters.http.compressor.{}", name)),
+    );
+    f.insert(s("typed_config"), Value::Mapping(tc));
+    Value::Mapping(f)
+}
+
+fn http_filter_ext_authz(e: &ExtAuthzSpec) -> Value {
+    let mut tc = Mapping::new();
+    tc.insert(
+        s("@type"),
+        s("type.googleapis.com/envoy.extensions.filters.http.ext_authz.v3.ExtAuthz"),
+    );
+    tc.insert(s("transport_api_version"), s("V3"));
+    tc.insert(s("failure_mode_allow"), b(e.failure_mode_allow));
+
+    match e.mode {
+        ExtAuthzMode::Http => {
+            let mut server_uri = Mapping::new();
+            server_uri.insert(s("uri"), s(format!("http://{}", e.upstream)));
+            server_uri.insert(s("cluster"), s(&e.upstream));
+            server_uri.insert(s("timeout"), s(&e.timeout));
+            let mut svc = Mapping::new();
+            svc.insert(s("server_uri"), Value::Mapping(server_uri));
+            if let Some(prefix) = &e.path_prefix {
+                svc.insert(s("path_prefix"), s(prefix));
+            }
+            if !e.allowed_upstream_headers.is_empty() {
+                let mut resp = Mapping::new();
+                resp.insert(
+                    s("allowed_upstream_headers"),
+                    header_patterns(&e.allowed_upstream_headers),
+                );
+                svc.insert(s("authorization_response"), Value::Mapping(resp));
+            }
+            tc.insert(s("http_service"), Value::Mapping(svc));
+        }
+        ExtAuthzMode::Grpc => {
+            let mut envoy_grpc = Mapping::new();
+            envoy_grpc.insert(s("cluster_name"), s(&e.upstream));
+            let mut svc = Mapping::new();
+            svc.insert(s("envoy_grpc"), Value::Mapping(envoy_grpc));
+            svc.insert(s("timeout"), s(&e.timeout));
+            tc.insert(s("grpc_service"), Value::Mapping(svc));
+        }
+    }
+    // gRPC requests carry all headers unless narrowed down here; HTTP
+    // requests carry a fixed few plus these
+    if !e.allowed_request_headers.is_empty() {
+        tc.insert(
+            s("allowed_headers"),
+            header_patterns(&e.allowed_request_headers),
+        );
+    }
+
+    let mut f = Mapping::new();
+    f.insert(s("name"), s("envoy.filters.http.ext_authz"));
+    f.insert(s("typed_config"), Value::Mapping(tc));
+    Value::Mapping(f)
+}
+
+/// ListStringMatcher of case-insensitive exact header names
+fn header_patterns(names: &[String]) -> Value {
+    let patterns = names
+        .iter()
+        .map(|h| {
+            let mut m = Mapping::new();
+            m.insert(s("exact"), s(h));
+            m.insert(s("ignore_case"), b(true));
+            Value::Mapping(m)
+        })
+        .collect();
+    let mut m = Mapping::new();
+    m.insert(s("patterns"), Value::Sequence(patterns));
+    Value::Mapping(m)
+}
+
+fn jwt_provider(p: &JwtProviderSpec) -> Value {
+    let mut m = Mapping::new();
+    if let Some(issuer) = &p.issuer {
+        m.insert(s("issuer"), s(issuer));
+    }
+    if !p.audiences.is_empty() {
+        m.insert(
+            s("audiences"),
+            Value::Sequence(p.audiences.iter().map(s).collect()),
+        );
+    }
+    if let Some(path) = &p.local_jwks {
+        m.insert(s("local_jwks"), filename(path));
+    }
+    if let Some(remote) = &p.remote_jwks {
+        let mut http_uri = Mapping::new();
+        http_uri.insert(s("uri"), s(&remote.uri));
+        http_uri.insert(s("cluster"), s(&remote.upstream));
+        http_uri.insert(s("timeout"), s(&remote.timeout));
+        let mut r = Mapping::new();
+        r.insert(s("http_uri"), Value::Mapping(http_uri));
+        if let Some(d) = &remote.cache_duration {
+            r.insert(s("cache_duration"), s(d));
+        }
+        m.insert(s("remote_jwks"), Value::Mapping(r));
+    }
+    if p.forward {
+        m.insert(s("forward"), b(true));
+    }
+    if let Some(h) = &p.forward_payload_header {
+        m.insert(s("forward_payload_header"), s(h));
+    }
+    if !p.claim_to_headers.is_empty() {
+        m.insert(
+            s("claim_to_headers"),
+            Value::Sequence(
+                p.claim_to_headers
+                    .iter()
+                    .map(|c| {
+                        let mut ch = Mapping::new();
+                        ch.insert(s("header_name"), s(&c.header));
+                        ch.insert(s("claim_name"), s(&c.claim));
+                        Value::Mapping(ch)
+                    })
+                    .collect(),
+            ),
+        );
+    }
+    Value::Mapping(m)
+}
+
+fn jwt_requirement(r: &JwtRequirementSpec) -> Value {
+    let provider = r.provider.as_deref().expect("validated");
+    let mut by_name = Mapping::new();
+    by_name.insert(s("provider_name"), s(provider));
+    if !r.allow_missing {
+        return Value::Mapping(by_name);
+    }
+    let mut missing = Mapping::new();
+    missing.insert(s("allow_missing"), Value::Mapping(Mapping::new()));
+    let mut any = Mapping::new();
+    any.insert(
+        s("requirements"),
+        Value::Sequence(vec![Value::Mapping(by_name), Value::Mapping(missing)]),
+    );
+    let mut m = Mapping::new();
+    m.insert(s("requires_any"), Value::Mapping(any));
+    Value::Mapping(m)
+}
+
+fn jwt_requirement_name(r: &JwtRequirementSpec) -> String {
+    let provider = r.provider.as_deref().expect("validated");
+    if r.allow_missing {
+        format!("{}_allow_missing", provider)
+    } else {
+        provider.to_string()
+    }
+}
+
+fn prefix_match(prefix: &str) -> Value {
+    let mut m = Mapping::new();
+    m.insert(s("prefix"), s(prefix));
+    Value::Mapping(m)
+}
+
 fn http_filter_grpc_web() -> Value {
     Value::Mapping({
         let mut f = Mapping::new();
@@ -705,6 +2077,35 @@ fn http_filter_grpc_web() -> Value {
     })
 }
 
+fn http_filter_grpc_stats() -> Value {
+    let mut tc = Mapping::new();
+    tc.insert(
+        s("@type"),
+        s("type.googleapis.com/envoy.extensions.filters.http.grpc_stats.v3.FilterConfig"),
+    );
+    tc.insert(s("emit_filter_state"), b(true));
+    // Without this the filter only counts methods it has a descriptor for,
+    // which a static config never provides
+    tc.insert(s("stats_for_all_methods"), b(true));
+
+    let mut f = Mapping::new();
+    f.insert(s("name"), s("envoy.filters.http.grpc_stats"));
+    f.insert(s("typed_config"), Value::Mapping(tc));
+    Value::Mapping(f)
+}
+
+/// HTTP/2 options for the `grpc` upstream preset. Keepalive pings detect
+/// half-dead connections that long-lived gRPC streams would otherwise hang on.
+fn grpc_http2_options() -> Value {
+    let mut ka = Mapping::new();
+    ka.insert(s("interval"), s("30s"));
+    ka.insert(s("timeout"), s("5s"));
+
+    let mut h2 = Mapping::new();
+    h2.insert(s("connection_keepalive"), Value::Mapping(ka));
+    Value::Mapping(h2)
+}
+
 fn header_value_option(h: &HeaderValueOption) -> Value {
     Value::Mapping({
         let mut m = Mapping::new();
@@ -760,42 +2161,289 @@ fn filter_chain_http(hcm_typed_config: Value) -> Mapping {
     fc
 }
 
-fn downstream_tls_socket(tls: &TlsSpec) -> Value {
-    Value::Mapping({
-        let mut ts = Mapping::new();
-        ts.insert(s("name"), s("envoy.transport_sockets.tls"));
-        ts.insert(s("typed_config"), Value::Mapping({
-            let mut tc = Mapping::new();
-            tc.insert(s("@type"), s("type.googleapis.com/envoy.extensions.transport_sockets.tls.v3.DownstreamTlsContext"));
-            tc.insert(s("common_tls_context"), Value::Mapping({
-                let mut ctc = Mapping::new();
-                ctc.insert(s("tls_certificates"), Value::Sequence(vec![Value::Mapping({
-                    let mut cert = Mapping::new();
-                    cert.insert(s("certificate_chain"), Value::Mapping({
-                        let mut cc = Mapping::new();
-                        cc.insert(s("filename"), s(&tls.cert_chain));
-                        cc
-                    }));
-                    cert.insert(s("private_key"), Value::Mapping({
-                        let mut pk = Mapping::new();
-                        pk.insert(s("filename"), s(&tls.private_key));
-                        pk
-                    }));
-                    cert
-                })]));
-                ctc
-            }));
-            tc
-        }));
-        ts
-    })
+/// Prepend the network filters enforcing `limits` to every filter chain of
+/// `listener`, ahead of its HCM or tcp_proxy: connection floods are rate
+/// limited before counting towards the concurrent connection limit. The
+/// chains share one token bucket through `share_key`; the connection_limit
+/// filter counts per chain, so `gen_layered_runtime` also caps the listener
+/// as a whole.
+fn apply_listener_limits(listener: &mut Mapping, limits: &ListenerLimitsSpec, stat_prefix: &str) {
+    let mut filters = Vec::new();
+    if let Some(rl) = &limits.connection_rate_limit {
+        let mut tc = Mapping::new();
+        tc.insert(
+            s("@type"),
+            s("type.googleapis.com/envoy.extensions.filters.network.local_ratelimit.v3.LocalRateLimit"),
+        );
+        tc.insert(s("stat_prefix"), s(format!("{stat_prefix}_conn_rl")));
+        tc.insert(
+            s("token_bucket"),
+            token_bucket(rl.max_tokens, rl.tokens_per_fill, &rl.fill_interval),
+        );
+        tc.insert(s("share_key"), s(stat_prefix));
+        let mut f = Mapping::new();
+        f.insert(s("name"), s("envoy.filters.network.local_ratelimit"));
+        f.insert(s("typed_config"), Value::Mapping(tc));
+        filters.push(Value::Mapping(f));
+    }
+    if let Some(max) = limits.max_connections {
+        let mut tc = Mapping::new();
+        tc.insert(
+            s("@type"),
+            s("type.googleapis.com/envoy.extensions.filters.network.connection_limit.v3.ConnectionLimit"),
+        );
+        tc.insert(s("stat_prefix"), s(format!("{stat_prefix}_conn_limit")));
+        tc.insert(s("max_connections"), n(max));
+        let mut f = Mapping::new();
+        f.insert(s("name"), s("envoy.filters.network.connection_limit"));
+        f.insert(s("typed_config"), Value::Mapping(tc));
+        filters.push(Value::Mapping(f));
+    }
+    if !filters.is_empty() {
+        if let Some(Value::Sequence(chains)) = listener.get_mut(s("filter_chains")) {
+            for fc in chains.iter_mut() {
+                if let Some(Value::Sequence(existing)) = fc.get_mut("filters") {
+                    existing.splice(0..0, filters.iter().cloned());
+                }
+            }
+        }
+    }
+    if let Some(bytes) = limits.per_connection_buffer_limit_bytes {
+        listener.insert(s("per_connection_buffer_limit_bytes"), n(bytes));
+    }
 }
 
-fn socket_addr(protocol: &str, address: &str, port: u16) -> Value {
-    Value::Mapping({
-        let mut a = Mapping::new();
-        a.insert(
-            s("socket_address"),
+fn downstream_tls_socket(
+    tls: &TlsSpec,
+    default_params: &TlsParamsSpec,
+    cert_sets: &[CertSetSpec],
+) -> Value {
+    let params = tls.params.resolve(default_params);
+    let mut ctc = Mapping::new();
+    if let Some(tls_params) = tls_params(&params) {
+        ctc.insert(s("tls_params"), tls_params);
+    }
+    if let Some(alpn) = &params.alpn_protocols {
+        ctc.insert(
+            s("alpn_protocols"),
+            Value::Sequence(alpn.iter().map(s).collect()),
+        );
+    }
+    ctc.insert(
+        s("tls_certificates"),
+        Value::Sequence(
+            tls.resolved_certificates(cert_sets)
+                .iter()
+                .map(|c| {
+                    let mut cert = Mapping::new();
+                    cert.insert(s("certificate_chain"), filename(&c.cert_chain));
+                    cert.insert(s("private_key"), filename(&c.private_key));
+                    if let Some(ocsp) = &c.ocsp_staple {
+                        cert.insert(s("ocsp_staple"), filename(ocsp));
+                    }
+                    Value::Mapping(cert)
+                })
+                .collect(),
+        ),
+    );
+
+    // Client certificate verification (mTLS)
+    if let Some(ca) = &tls.client_ca {
+        let mut vc = Mapping::new();
+        vc.insert(s("trusted_ca"), filename(ca));
+        if !tls.verify_san.is_empty() {
+            vc.insert(
+                s("match_typed_subject_alt_names"),
+                dns_san_matchers(&tls.verify_san),
+            );
+        }
+        if !tls.verify_spki.is_empty() {
+            vc.insert(
+                s("verify_certificate_spki"),
+                Value::Sequence(tls.verify_spki.iter().map(s).collect()),
+            );
+        }
+        if !tls.verify_hash.is_empty() {
+            vc.insert(
+                s("verify_certificate_hash"),
+                Value::Sequence(tls.verify_hash.iter().map(s).collect()),
+            );
+        }
+        if let Some(crl) = &tls.crl {
+            vc.insert(s("crl"), filename(crl));
+        }
+        ctc.insert(s("validation_context"), Value::Mapping(vc));
+    }
+
+    let mut tc = Mapping::new();
+    tc.insert(
+        s("@type"),
+        s("type.googleapis.com/envoy.extensions.transport_sockets.tls.v3.DownstreamTlsContext"),
+    );
+    tc.insert(s("common_tls_context"), Value::Mapping(ctc));
+    if tls.require_client_certificate {
+        tc.insert(s("require_client_certificate"), b(true));
+    }
+
+    let mut ts = Mapping::new();
+    ts.insert(s("name"), s("envoy.transport_sockets.tls"));
+    ts.insert(s("typed_config"), Value::Mapping(tc));
+    Value::Mapping(ts)
+}
+
+fn tls_params(params: &TlsParamsSpec) -> Option<Value> {
+    let mut m = Mapping::new();
+    if let Some(v) = params.tls_minimum_protocol_version {
+        m.insert(s("tls_minimum_protocol_version"), s(v.as_str()));
+    }
+    if let Some(v) = params.tls_maximum_protocol_version {
+        m.insert(s("tls_maximum_protocol_version"), s(v.as_str()));
+    }
+    if let Some(ciphers) = &params.cipher_suites {
+        m.insert(
+            s("cipher_suites"),
+            Value::Sequence(ciphers.iter().map(s).collect()),
+        );
+    }
+    if let Some(curves) = &params.ecdh_curves {
+        m.insert(
+            s("ecdh_curves"),
+            Value::Sequence(curves.iter().map(s).collect()),
+        );
+    }
+    (!m.is_empty()).then_some(Value::Mapping(m))
+}
+
+fn upstream_tls_socket(tls: &UpstreamTlsSpec, u: &UpstreamSpec) -> Value {
+    let mut ctc = Mapping::new();
+    if let (Some(chain), Some(key)) = (&tls.client_cert_chain, &tls.client_private_key) {
+        ctc.insert(
+            s("tls_certificates"),
+            Value::Sequence(vec![Value::Mapping({
+                let mut cert = Mapping::new();
+                cert.insert(s("certificate_chain"), filename(chain));
+                cert.insert(s("private_key"), filename(key));
+                cert
+            })]),
+        );
+    }
+    if let Some(ca) = &tls.trusted_ca {
+        let mut vc = Mapping::new();
+        vc.insert(s("trusted_ca"), filename(ca));
+        if !tls.verify_san.is_empty() {
+            vc.insert(
+                s("match_typed_subject_alt_names"),
+                dns_san_matchers(&tls.verify_san),
+            );
+        }
+        ctc.insert(s("validation_context"), Value::Mapping(vc));
+    }
+    if !tls.alpn_protocols.is_empty() {
+        ctc.insert(
+            s("alpn_protocols"),
+            Value::Sequence(tls.alpn_protocols.iter().map(s).collect()),
+        );
+    }
+
+    let mut tc = Mapping::new();
+    tc.insert(
+        s("@type"),
+        s("type.googleapis.com/envoy.extensions.transport_sockets.tls.v3.UpstreamTlsContext"),
+    );
+    if let Some(sni) = tls.sni.as_deref().or_else(|| default_sni(u)) {
+        tc.insert(s("sni"), s(sni));
+    }
+    tc.insert(s("common_tls_context"), Value::Mapping(ctc));
+
+    let mut ts = Mapping::new();
+    ts.insert(s("name"), s("envoy.transport_sockets.tls"));
+    ts.insert(s("typed_config"), Value::Mapping(tc));
+    Value::Mapping(ts)
+}
+
+/// Host name of the first endpoint: its `hostname`, else its address
+/// unless that is an IP
+fn default_sni(u: &UpstreamSpec) -> Option<&str> {
+    let ep = u.all_endpoints().next()?;
+    match &ep.hostname {
+        Some(host) => Some(host),
+        None if ep.address.parse::<IpAddr>().is_err() => Some(&ep.address),
+        None => None,
+    }
+}
+
+/// `{ filename: <path> }` data source
+fn filename(path: &str) -> Value {
+    let mut m = Mapping::new();
+    m.insert(s("filename"), s(path));
+    Value::Mapping(m)
+}
+
+fn dns_san_matchers(sans: &[String]) -> Value {
+    Value::Sequence(
+        sans.iter()
+            .map(|san| {
+                let mut m = Mapping::new();
+                m.insert(s("san_type"), s("DNS"));
+                m.insert(s("matcher"), string_matcher_exact(san));
+                Value::Mapping(m)
+            })
+            .collect(),
+    )
+}
+
+fn string_matcher(m: &StringMatchSpec) -> Value {
+    let mut out = Mapping::new();
+    if let Some(v) = &m.exact {
+        out.insert(s("exact"), s(v));
+    } else if let Some(v) = &m.prefix {
+        out.insert(s("prefix"), s(v));
+    } else if let Some(v) = &m.regex {
+        let mut re = Mapping::new();
+        re.insert(s("regex"), s(v));
+        out.insert(s("safe_regex"), Value::Mapping(re));
+    }
+    Value::Mapping(out)
+}
+
+fn string_matcher_exact(value: &str) -> Value {
+    let mut m = Mapping::new();
+    m.insert(s("exact"), s(value));
+    Value::Mapping(m)
+}
+
+/// `address` plus any `additional_addresses` of a built-in listener.
+fn insert_builtin_addresses(listener: &mut Mapping, spec: &BuiltinListenerSpec, port: u16) {
+    let mut address = socket_addr("TCP", &spec.address, port);
+    if spec.ipv4_compat {
+        if let Some(Value::Mapping(sa)) = address.get_mut("socket_address") {
+            sa.insert(s("ipv4_compat"), b(true));
+        }
+    }
+    listener.insert(s("address"), address);
+
+    if !spec.additional_addresses.is_empty() {
+        let extra = spec
+            .additional_addresses
+            .iter()
+            .map(|a| {
+                let mut m = Mapping::new();
+                m.insert(
+                    s("address"),
+                    socket_addr("TCP", &a.address, a.port.unwrap_or(port)),
+                );
+                Value::Mapping(m)
+            })
+            .collect();
+        listener.insert(s("additional_addresses"), Value::Sequence(extra));
+    }
+}
+
+fn socket_addr(protocol: &str, address: &str, port: u16) -> Value {
+    Value::Mapping({
+        let mut a = Mapping::new();
+        a.insert(
+            s("socket_address"),
             Value::Mapping({
                 let mut sa = Mapping::new();
                 sa.insert(s("protocol"), s(protocol));
@@ -808,26 +2456,318 @@ fn socket_addr(protocol: &str, address: &str, port: u16) -> Value {
     })
 }
 
-fn stdout_access_log(log: &AccessLogSpec) -> Value {
-    // Initial draft supports stdout file logger
+/// Access log entries for a connection manager: the configured sink followed
+/// by any extra `sinks`. Filter runtime keys are scoped by `scope` (the
+/// connection manager's stat_prefix) and the sink's position, so overriding
+/// one log's sampling leaves the others alone.
+fn access_log(log: &AccessLogSpec, scope: &str) -> Value {
+    Value::Sequence(
+        std::iter::once(log)
+            .chain(&log.sinks)
+            .enumerate()
+            .map(|(i, sink)| {
+                let runtime_prefix = match i {
+                    0 => format!("access_log.{scope}"),
+                    _ => format!("access_log.{scope}.sink_{i}"),
+                };
+                access_log_entry(sink, &runtime_prefix)
+            })
+            .collect(),
+    )
+}
+
+fn access_log_entry(log: &AccessLogSpec, runtime_prefix: &str) -> Value {
+    let (name, type_url) = match log.r#type {
+        AccessLogType::Stdout => (
+            "envoy.access_loggers.stdout",
+            "type.googleapis.com/envoy.extensions.access_loggers.stream.v3.StdoutAccessLog",
+        ),
+        AccessLogType::Stderr => (
+            "envoy.access_loggers.stderr",
+            "type.googleapis.com/envoy.extensions.access_loggers.stream.v3.StderrAccessLog",
+        ),
+        AccessLogType::File => (
+            "envoy.access_loggers.file",
+            "type.googleapis.com/envoy.extensions.access_loggers.file.v3.FileAccessLog",
+        ),
+        AccessLogType::OpenTelemetry => (
+            "envoy.access_loggers.open_telemetry",
+            "type.googleapis.com/envoy.extensions.access_loggers.open_telemetry.v3.OpenTelemetryAccessLogConfig",
+        ),
+        AccessLogType::Grpc => (
+            "envoy.access_loggers.http_grpc",
+            "type.googleapis.com/envoy.extensions.access_loggers.grpc.v3.HttpGrpcAccessLogConfig",
+        ),
+    };
+
+    let mut tc = Mapping::new();
+    tc.insert(s("@type"), s(type_url));
+    match log.r#type {
+        AccessLogType::File => {
+            tc.insert(s("path"), s(&log.path));
+        }
+        AccessLogType::OpenTelemetry | AccessLogType::Grpc => {
+            tc.insert(s("common_config"), grpc_access_log_common(log));
+        }
+        AccessLogType::Stdout | AccessLogType::Stderr => {}
+    }
+    if log.r#type == AccessLogType::OpenTelemetry {
+        // OTLP log records carry the text format as the body and JSON
+        // fields as attributes instead of a log_format block
+        if let Some(format) = &log.format {
+            let mut body = Mapping::new();
+            body.insert(s("string_value"), s(format));
+            tc.insert(s("body"), Value::Mapping(body));
+        }
+        if let Some(json) = &log.json_format {
+            let values = json
+                .iter()
+                .map(|(key, op)| {
+                    let mut value = Mapping::new();
+                    value.insert(s("string_value"), s(op));
+                    let mut kv = Mapping::new();
+                    kv.insert(s("key"), s(key));
+                    kv.insert(s("value"), Value::Mapping(value));
+                    Value::Mapping(kv)
+                })
+                .collect();
+            let mut attributes = Mapping::new();
+            attributes.insert(s("values"), Value::Sequence(values));
+            tc.insert(s("attributes"), Value::Mapping(attributes));
+        }
+    } else if let Some(format) = access_log_format(log) {
+        tc.insert(s("log_format"), format);
+    }
+
     let mut entry = Mapping::new();
-    entry.insert(s("name"), s("envoy.access_loggers.file"));
-    entry.insert(
-        s("typed_config"),
-        Value::Mapping({
-            let mut tc = Mapping::new();
+    entry.insert(s("name"), s(name));
+    if let Some(filter) = log
+        .filter
+        .as_ref()
+        .and_then(|f| access_log_filter(f, runtime_prefix))
+    {
+        entry.insert(s("filter"), filter);
+    }
+    entry.insert(s("typed_config"), Value::Mapping(tc));
+    Value::Mapping(entry)
+}
+
+fn grpc_access_log_common(log: &AccessLogSpec) -> Value {
+    let cluster = log.upstream.as_deref().expect("validated: upstream exists");
+    let mut envoy_grpc = Mapping::new();
+    envoy_grpc.insert(s("cluster_name"), s(cluster));
+    let mut grpc_service = Mapping::new();
+    grpc_service.insert(s("envoy_grpc"), Value::Mapping(envoy_grpc));
+
+    let mut m = Mapping::new();
+    m.insert(
+        s("log_name"),
+        s(log.log_name.as_deref().unwrap_or("envoy_access_log")),
+    );
+    m.insert(s("grpc_service"), Value::Mapping(grpc_service));
+    m.insert(s("transport_api_version"), s("V3"));
+    Value::Mapping(m)
+}
+
+fn access_log_format(log: &AccessLogSpec) -> Option<Value> {
+    let mut m = Mapping::new();
+    if let Some(json) = &log.json_format {
+        let mut fields = Mapping::new();
+        for (key, op) in json {
+            fields.insert(s(key), s(op));
+        }
+        m.insert(s("json_format"), Value::Mapping(fields));
+    } else if let Some(format) = &log.format {
+        // Text formats are emitted verbatim, so each entry needs its own newline
+        let line = if format.ends_with('\n') {
+            format.clone()
+        } else {
+            format!("{format}\n")
+        };
+        let mut source = Mapping::new();
+        source.insert(s("inline_string"), s(&line));
+        m.insert(s("text_format_source"), Value::Mapping(source));
+    } else {
+        return None;
+    }
+    Some(Value::Mapping(m))
+}
+
+/// Combine the configured conditions into a single access log filter;
+/// several conditions are joined with `and_filter`.
+fn access_log_filter(filter: &AccessLogFilterSpec, runtime_prefix: &str) -> Option<Value> {
+    let mut filters = Vec::new();
+
+    let ranges: Vec<Value> = filter
+        .status_codes
+        .iter()
+        .enumerate()
+        .map(|(i, r)| {
+            let key = format!("{runtime_prefix}.status_code_{i}");
+            let mut bounds = Vec::new();
+            if let Some(min) = r.min {
+                bounds.push(status_code_filter("GE", min, &key));
+            }
+            if let Some(max) = r.max {
+                bounds.push(status_code_filter("LE", max, &key));
+            }
+            combine_filters("and_filter", bounds)
+        })
+        .collect();
+    if !ranges.is_empty() {
+        filters.push(combine_filters("or_filter", ranges));
+    }
+
+    if let Some(percent) = filter.sample_percent {
+        let mut sampled = Mapping::new();
+        sampled.insert(s("numerator"), n(percent));
+        sampled.insert(s("denominator"), s("HUNDRED"));
+        let mut runtime = Mapping::new();
+        runtime.insert(
+            s("runtime_key"),
+            s(format!("{runtime_prefix}.sample_percent")),
+        );
+        runtime.insert(s("percent_sampled"), Value::Mapping(sampled));
+        let mut m = Mapping::new();
+        m.insert(s("runtime_filter"), Value::Mapping(runtime));
+        filters.push(Value::Mapping(m));
+    }
+
+    if filter.not_health_check {
+        let mut m = Mapping::new();
+        m.insert(s("not_health_check_filter"), Value::Mapping(Mapping::new()));
+        filters.push(Value::Mapping(m));
+    }
+
+    if filters.is_empty() {
+        None
+    } else {
+        Some(combine_filters("and_filter", filters))
+    }
+}
+
+fn status_code_filter(op: &str, code: u16, runtime_key: &str) -> Value {
+    let mut default_value = Mapping::new();
+    default_value.insert(s("default_value"), n(code));
+    default_value.insert(
+        s("runtime_key"),
+        s(format!("{runtime_key}_{}", op.to_lowercase())),
+    );
+    let mut comparison = Mapping::new();
+    comparison.insert(s("op"), s(op));
+    comparison.insert(s("value"), Value::Mapping(default_value));
+    let mut status = Mapping::new();
+    status.insert(s("comparison"), Value::Mapping(comparison));
+    let mut m = Mapping::new();
+    m.insert(s("status_code_filter"), Value::Mapping(status));
+    Value::Mapping(m)
+}
+
+/// Wrap filters in `and_filter`/`or_filter`, or return a lone filter as is.
+fn combine_filters(kind: &str, mut filters: Vec<Value>) -> Value {
+    if filters.len() == 1 {
+        return filters.remove(0);
+    }
+    let mut inner = Mapping::new();
+    inner.insert(s("filters"), Value::Sequence(filters));
+    let mut m = Mapping::new();
+    m.insert(s(kind), Value::Mapping(inner));
+    Value::Mapping(m)
+}
+
+/// HCM `tracing` block. Domain sampling overrides the common percentages
+/// field by field; domain tags follow the common ones.
+fn tracing_config(tracing: &TracingSpec, domain: Option<&DomainTracingSpec>) -> Value {
+    let mut m = Mapping::new();
+    let sampling = match domain {
+        Some(d) => d.sampling.or(&tracing.sampling),
+        None => tracing.sampling.clone(),
+    };
+    for (key, value) in [
+        ("client_sampling", sampling.client_sampling),
+        ("random_sampling", sampling.random_sampling),
+        ("overall_sampling", sampling.overall_sampling),
+    ] {
+        if let Some(v) = value {
+            let mut percent = Mapping::new();
+            percent.insert(s("value"), Value::from(v));
+            m.insert(s(key), Value::Mapping(percent));
+        }
+    }
+
+    let tags: Vec<Value> = tracing
+        .custom_tags
+        .iter()
+        .chain(domain.into_iter().flat_map(|d| &d.custom_tags))
+        .map(custom_tag)
+        .collect();
+    if !tags.is_empty() {
+        m.insert(s("custom_tags"), Value::Sequence(tags));
+    }
+    m.insert(s("provider"), tracing_provider(tracing));
+    Value::Mapping(m)
+}
+
+fn tracing_provider(tracing: &TracingSpec) -> Value {
+    let mut tc = Mapping::new();
+    let name = match tracing.provider {
+        TracingProvider::OpenTelemetry => {
             tc.insert(
                 s("@type"),
-                s("type.googleapis.com/envoy.extensions.access_loggers.file.v3.FileAccessLog"),
+                s("type.googleapis.com/envoy.config.trace.v3.OpenTelemetryConfig"),
             );
-            tc.insert(s("path"), s(&log.path));
-            tc
-        }),
-    );
-    Value::Sequence(vec![Value::Mapping(entry)])
+            let mut envoy_grpc = Mapping::new();
+            envoy_grpc.insert(s("cluster_name"), s(&tracing.upstream));
+            let mut grpc_service = Mapping::new();
+            grpc_service.insert(s("envoy_grpc"), Value::Mapping(envoy_grpc));
+            tc.insert(s("grpc_service"), Value::Mapping(grpc_service));
+            tc.insert(s("service_name"), s(&tracing.service_name));
+            "envoy.tracers.opentelemetry"
+        }
+        TracingProvider::Zipkin => {
+            tc.insert(
+                s("@type"),
+                s("type.googleapis.com/envoy.config.trace.v3.ZipkinConfig"),
+            );
+            tc.insert(s("collector_cluster"), s(&tracing.upstream));
+            tc.insert(
+                s("collector_endpoint"),
+                s(tracing
+                    .collector_endpoint
+                    .as_deref()
+                    .unwrap_or("/api/v2/spans")),
+            );
+            tc.insert(s("collector_endpoint_version"), s("HTTP_JSON"));
+            "envoy.tracers.zipkin"
+        }
+    };
+
+    let mut m = Mapping::new();
+    m.insert(s("name"), s(name));
+    m.insert(s("typed_config"), Value::Mapping(tc));
+    Value::Mapping(m)
+}
+
+fn custom_tag(tag: &CustomTagSpec) -> Value {
+    let mut m = Mapping::new();
+    m.insert(s("tag"), s(&tag.tag));
+    if let Some(value) = &tag.literal {
+        let mut l = Mapping::new();
+        l.insert(s("value"), s(value));
+        m.insert(s("literal"), Value::Mapping(l));
+    } else if let Some(name) = &tag.request_header {
+        let mut h = Mapping::new();
+        h.insert(s("name"), s(name));
+        m.insert(s("request_header"), Value::Mapping(h));
+    } else if let Some(name) = &tag.environment {
+        let mut e = Mapping::new();
+        e.insert(s("name"), s(name));
+        m.insert(s("environment"), Value::Mapping(e));
+    }
+    Value::Mapping(m)
 }
 
-fn sanitize_name(domain: &str) -> String {
+pub(crate) fn sanitize_name(domain: &str) -> String {
     domain
         .chars()
         .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
@@ -848,6 +2788,8 @@ fn b(x: bool) -> Value {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_fixtures::*;
+    use indexmap::IndexMap;
     use serde_yaml::Value;
 
     #[test]
@@ -866,19 +2808,19 @@ mod tests {
         let result = socket_addr("TCP", "127.0.0.1", 8080);
         match &result {
             Value::Mapping(m) => {
-                let socket_addr = m.get(&Value::String("socket_address".to_string())).unwrap();
+                let socket_addr = m.get(Value::String("socket_address".to_string())).unwrap();
                 match socket_addr {
                     Value::Mapping(sa) => {
                         assert_eq!(
-                            sa.get(&Value::String("protocol".to_string())).unwrap(),
+                            sa.get(Value::String("protocol".to_string())).unwrap(),
                             &Value::String("TCP".to_string())
                         );
                         assert_eq!(
-                            sa.get(&Value::String("address".to_string())).unwrap(),
+                            sa.get(Value::String("address".to_string())).unwrap(),
                             &Value::String("127.0.0.1".to_string())
                         );
                         assert_eq!(
-                            sa.get(&Value::String("port_value".to_string())).unwrap(),
+                            sa.get(Value::String("port_value".to_string())).unwrap(),
                             &Value::Number(8080.into())
                         );
                     }
@@ -896,15 +2838,18 @@ mod tests {
             admin: AdminSpec {
                 address: "0.0.0.0".to_string(),
                 port: 9901,
+                ..Default::default()
             },
             defaults: DefaultsSpec {
                 route_timeout: "60s".to_string(),
                 http_default_upstream: "default_http".to_string(),
                 tls_passthrough_upstream: "default_tls".to_string(),
+                ..Default::default()
             },
             access_log: AccessLogSpec {
-                r#type: "stdout".to_string(),
+                r#type: AccessLogType::Stdout,
                 path: "/dev/stdout".to_string(),
+                ..Default::default()
             },
             validate: ValidateSpec::Native {
                 user: "envoy".to_string(),
@@ -912,34 +2857,43 @@ mod tests {
                 config_path: "/etc/envoy/envoy.yaml".to_string(),
             },
             listeners: ListenersSpec::default(),
+            certs: vec![],
             domains: vec![],
             upstreams: vec![
                 UpstreamSpec {
                     name: "default_http".to_string(),
                     connect_timeout: "5s".to_string(),
-                    r#type: "STATIC".to_string(),
-                    lb_policy: "ROUND_ROBIN".to_string(),
+                    r#type: ClusterType::Static,
+                    lb_policy: LbPolicy::RoundRobin,
                     http2: false,
                     endpoints: vec![Endpoint {
                         address: "127.0.0.1".to_string(),
                         port: 8080,
+                        ..Default::default()
                     }],
+                    ..Default::default()
                 },
                 UpstreamSpec {
                     name: "default_tls".to_string(),
                     connect_timeout: "5s".to_string(),
-                    r#type: "STATIC".to_string(),
-                    lb_policy: "ROUND_ROBIN".to_string(),
+                    r#type: ClusterType::Static,
+                    lb_policy: LbPolicy::RoundRobin,
                     http2: false,
                     endpoints: vec![Endpoint {
                         address: "127.0.0.1".to_string(),
                         port: 8443,
+                        ..Default::default()
                     }],
+                    ..Default::default()
                 },
             ],
             policies: PoliciesSpec {
                 local_ratelimits: Default::default(),
+                ..Default::default()
             },
+            tracing: None,
+            stats: StatsSpec::default(),
+            overload: OverloadSpec::default(),
         };
 
         let result = generate_envoy_yaml(&loaded);
@@ -949,8 +2903,8 @@ mod tests {
         match &yaml_value {
             Value::Mapping(m) => {
                 // Check that we have the expected top-level keys
-                assert!(m.contains_key(&Value::String("admin".to_string())));
-                assert!(m.contains_key(&Value::String("static_resources".to_string())));
+                assert!(m.contains_key(Value::String("admin".to_string())));
+                assert!(m.contains_key(Value::String("static_resources".to_string())));
             }
             _ => panic!("Expected mapping for root"),
         }
@@ -963,15 +2917,18 @@ mod tests {
             admin: AdminSpec {
                 address: "0.0.0.0".to_string(),
                 port: 9901,
+                ..Default::default()
             },
             defaults: DefaultsSpec {
                 route_timeout: "60s".to_string(),
                 http_default_upstream: "default_http".to_string(),
                 tls_passthrough_upstream: "default_tls".to_string(),
+                ..Default::default()
             },
             access_log: AccessLogSpec {
-                r#type: "stdout".to_string(),
+                r#type: AccessLogType::Stdout,
                 path: "/dev/stdout".to_string(),
+                ..Default::default()
             },
             validate: ValidateSpec::Native {
                 user: "envoy".to_string(),
@@ -979,68 +2936,83 @@ mod tests {
                 config_path: "/etc/envoy/envoy.yaml".to_string(),
             },
             listeners: ListenersSpec::default(),
+            certs: vec![],
             domains: vec![DomainSpec {
                 domain: "example.com".to_string(),
                 mode: "terminate_https_443".to_string(),
                 tls: Some(TlsSpec {
-                    cert_chain: "/path/to/cert".to_string(),
-                    private_key: "/path/to/key".to_string(),
+                    cert_chain: Some("/path/to/cert".to_string()),
+                    private_key: Some("/path/to/key".to_string()),
+                    ..Default::default()
                 }),
                 routes: vec![RouteSpec {
                     m: MatchSpec {
                         prefix: Some("/api".to_string()),
                         path: None,
                         headers: vec![],
+                        ..Default::default()
                     },
                     to_upstream: Some("api_backend".to_string()),
                     timeout: Some("30s".to_string()),
                     per_filter_config: None,
                     prefix_rewrite: None,
                     direct_response: None,
+                    ..Default::default()
                 }],
                 http_connection_manager: None,
                 normalize_path: None,
                 merge_slashes: None,
                 aws_signing: None,
+                ..Default::default()
             }],
             upstreams: vec![
                 UpstreamSpec {
                     name: "api_backend".to_string(),
                     connect_timeout: "5s".to_string(),
-                    r#type: "STATIC".to_string(),
-                    lb_policy: "ROUND_ROBIN".to_string(),
+                    r#type: ClusterType::Static,
+                    lb_policy: LbPolicy::RoundRobin,
                     http2: false,
                     endpoints: vec![Endpoint {
                         address: "127.0.0.1".to_string(),
                         port: 8080,
+                        ..Default::default()
                     }],
+                    ..Default::default()
                 },
                 UpstreamSpec {
                     name: "default_http".to_string(),
                     connect_timeout: "5s".to_string(),
-                    r#type: "STATIC".to_string(),
-                    lb_policy: "ROUND_ROBIN".to_string(),
+                    r#type: ClusterType::Static,
+                    lb_policy: LbPolicy::RoundRobin,
                     http2: false,
                     endpoints: vec![Endpoint {
                         address: "127.0.0.1".to_string(),
                         port: 8080,
+                        ..Default::default()
                     }],
+                    ..Default::default()
                 },
                 UpstreamSpec {
                     name: "default_tls".to_string(),
                     connect_timeout: "5s".to_string(),
-                    r#type: "STATIC".to_string(),
-                    lb_policy: "ROUND_ROBIN".to_string(),
+                    r#type: ClusterType::Static,
+                    lb_policy: LbPolicy::RoundRobin,
                     http2: false,
                     endpoints: vec![Endpoint {
                         address: "127.0.0.1".to_string(),
                         port: 8443,
+                        ..Default::default()
                     }],
+                    ..Default::default()
                 },
             ],
             policies: PoliciesSpec {
                 local_ratelimits: Default::default(),
+                ..Default::default()
             },
+            tracing: None,
+            stats: StatsSpec::default(),
+            overload: OverloadSpec::default(),
         };
 
         let result = generate_envoy_yaml(&loaded);
@@ -1050,17 +3022,17 @@ mod tests {
         match &yaml_value {
             Value::Mapping(m) => {
                 // Check that we have the expected top-level keys
-                assert!(m.contains_key(&Value::String("admin".to_string())));
-                assert!(m.contains_key(&Value::String("static_resources".to_string())));
+                assert!(m.contains_key(Value::String("admin".to_string())));
+                assert!(m.contains_key(Value::String("static_resources".to_string())));
 
                 // Check static_resources structure
                 let static_resources = m
-                    .get(&Value::String("static_resources".to_string()))
+                    .get(Value::String("static_resources".to_string()))
                     .unwrap();
                 match static_resources {
                     Value::Mapping(sr) => {
-                        assert!(sr.contains_key(&Value::String("listeners".to_string())));
-                        assert!(sr.contains_key(&Value::String("clusters".to_string())));
+                        assert!(sr.contains_key(Value::String("listeners".to_string())));
+                        assert!(sr.contains_key(Value::String("clusters".to_string())));
                     }
                     _ => panic!("Expected mapping for static_resources"),
                 }
@@ -1069,6 +3041,1722 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_gen_cluster_with_outlier_detection_and_circuit_breakers() {
+        let upstream = UpstreamSpec {
+            outlier_detection: Some(OutlierDetectionSpec {
+                consecutive_5xx: Some(5),
+                consecutive_gateway_failure: None,
+                interval: Some("10s".to_string()),
+                base_ejection_time: Some("30s".to_string()),
+                max_ejection_percent: Some(50),
+            }),
+            circuit_breakers: Some(CircuitBreakersSpec {
+                default: Some(CircuitBreakerThresholds {
+                    max_connections: Some(1024),
+                    max_pending_requests: None,
+                    max_requests: None,
+                    max_retries: Some(3),
+                }),
+                high: None,
+            }),
+            ..upstream("api", "api", 8080)
+        };
+
+        let cluster = Value::Mapping(gen_cluster(&upstream));
+        let od = &cluster["outlier_detection"];
+        assert_eq!(od["consecutive_5xx"], Value::Number(5.into()));
+        assert_eq!(od["interval"], Value::String("10s".to_string()));
+        assert_eq!(od["base_ejection_time"], Value::String("30s".to_string()));
+        assert_eq!(od["max_ejection_percent"], Value::Number(50.into()));
+        assert!(od.get("consecutive_gateway_failure").is_none());
+
+        let thresholds = cluster["circuit_breakers"]["thresholds"]
+            .as_sequence()
+            .unwrap();
+        assert_eq!(thresholds.len(), 1);
+        assert_eq!(
+            thresholds[0]["priority"],
+            Value::String("DEFAULT".to_string())
+        );
+        assert_eq!(thresholds[0]["max_connections"], Value::Number(1024.into()));
+        assert_eq!(thresholds[0]["max_retries"], Value::Number(3.into()));
+        assert!(thresholds[0].get("max_requests").is_none());
+    }
+
+    #[test]
+    fn test_gen_cluster_with_upstream_tls() {
+        let secure = UpstreamSpec {
+            tls: Some(UpstreamTlsSpec {
+                sni: Some("backend.internal".to_string()),
+                trusted_ca: Some("/etc/ssl/ca.pem".to_string()),
+                insecure_skip_verify: false,
+                client_cert_chain: Some("/etc/envoy/client.pem".to_string()),
+                client_private_key: Some("/etc/envoy/client.key".to_string()),
+                alpn_protocols: vec!["h2".to_string()],
+                verify_san: vec!["backend.internal".to_string()],
+            }),
+            ..upstream("secure", "backend.internal", 443)
+        };
+
+        let cluster = Value::Mapping(gen_cluster(&secure));
+        let ts = &cluster["transport_socket"];
+        assert_eq!(
+            ts["name"],
+            Value::String("envoy.transport_sockets.tls".into())
+        );
+        let tc = &ts["typed_config"];
+        assert_eq!(tc["sni"], Value::String("backend.internal".into()));
+        let ctc = &tc["common_tls_context"];
+        assert_eq!(
+            ctc["tls_certificates"][0]["certificate_chain"]["filename"],
+            Value::String("/etc/envoy/client.pem".into())
+        );
+        assert_eq!(
+            ctc["validation_context"]["trusted_ca"]["filename"],
+            Value::String("/etc/ssl/ca.pem".into())
+        );
+        assert_eq!(
+            ctc["validation_context"]["match_typed_subject_alt_names"][0]["matcher"]["exact"],
+            Value::String("backend.internal".into())
+        );
+        assert_eq!(ctc["alpn_protocols"][0], Value::String("h2".into()));
+
+        // Without sni the endpoint's DNS name is sent; IP endpoints send none
+        let mut insecure = UpstreamSpec {
+            tls: Some(UpstreamTlsSpec {
+                insecure_skip_verify: true,
+                ..Default::default()
+            }),
+            ..upstream("secure", "backend.internal", 443)
+        };
+        let cluster = Value::Mapping(gen_cluster(&insecure));
+        let tc = &cluster["transport_socket"]["typed_config"];
+        assert_eq!(tc["sni"], Value::String("backend.internal".into()));
+        assert!(tc["common_tls_context"].get("validation_context").is_none());
+
+        insecure.endpoints[0].address = "10.0.0.7".to_string();
+        let cluster = Value::Mapping(gen_cluster(&insecure));
+        assert!(cluster["transport_socket"]["typed_config"]
+            .get("sni")
+            .is_none());
+    }
+
+    #[test]
+    fn test_gen_cluster_with_localities() {
+        let upstream = UpstreamSpec {
+            name: "api".to_string(),
+            connect_timeout: "5s".to_string(),
+            r#type: ClusterType::StrictDns,
+            lb_policy: LbPolicy::RoundRobin,
+            localities: vec![
+                LocalitySpec {
+                    region: Some("eu-west".to_string()),
+                    zone: Some("dc1".to_string()),
+                    sub_zone: None,
+                    priority: 0,
+                    load_balancing_weight: Some(80),
+                    endpoints: vec![Endpoint {
+                        address: "api-1.dc1".to_string(),
+                        port: 8080,
+                        weight: Some(3),
+                        hostname: Some("api-1".to_string()),
+                    }],
+                },
+                LocalitySpec {
+                    region: Some("eu-central".to_string()),
+                    zone: None,
+                    sub_zone: None,
+                    priority: 1,
+                    load_balancing_weight: Some(20),
+                    endpoints: vec![Endpoint {
+                        address: "api-1.dc2".to_string(),
+                        port: 8080,
+                        ..Default::default()
+                    }],
+                },
+            ],
+            ..Default::default()
+        };
+
+        let cluster = Value::Mapping(gen_cluster(&upstream));
+        assert!(cluster["common_lb_config"]
+            .get("locality_weighted_lb_config")
+            .is_some());
+
+        let groups = cluster["load_assignment"]["endpoints"]
+            .as_sequence()
+            .unwrap();
+        assert_eq!(groups.len(), 2);
+        assert_eq!(
+            groups[0]["locality"]["zone"],
+            Value::String("dc1".to_string())
+        );
+        assert_eq!(groups[0]["load_balancing_weight"], Value::Number(80.into()));
+        assert!(groups[0].get("priority").is_none());
+        let ep = &groups[0]["lb_endpoints"][0];
+        assert_eq!(ep["load_balancing_weight"], Value::Number(3.into()));
+        assert_eq!(
+            ep["endpoint"]["hostname"],
+            Value::String("api-1".to_string())
+        );
+        assert_eq!(groups[1]["priority"], Value::Number(1.into()));
+        assert!(groups[1]["locality"].get("zone").is_none());
+    }
+
+    #[test]
+    fn test_gen_cluster_with_maglev_and_route_hash_policy() {
+        let upstream = UpstreamSpec {
+            name: "sessions".to_string(),
+            connect_timeout: "5s".to_string(),
+            r#type: ClusterType::StrictDns,
+            lb_policy: LbPolicy::Maglev,
+            maglev: Some(MaglevSpec {
+                table_size: Some(65537),
+            }),
+            endpoints: vec![Endpoint {
+                address: "app".to_string(),
+                port: 8080,
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let cluster = Value::Mapping(gen_cluster(&upstream));
+        assert_eq!(cluster["lb_policy"], Value::String("MAGLEV".to_string()));
+        assert_eq!(
+            cluster["maglev_lb_config"]["table_size"],
+            Value::Number(65537.into())
+        );
+
+        let route = RouteSpec {
+            m: MatchSpec {
+                prefix: Some("/".to_string()),
+                ..Default::default()
+            },
+            to_upstream: Some("sessions".to_string()),
+            hash_policy: vec![
+                HashPolicySpec {
+                    header: None,
+                    cookie: Some(HashCookieSpec {
+                        name: "sticky".to_string(),
+                        ttl: Some("3600s".to_string()),
+                        path: Some("/".to_string()),
+                    }),
+                    source_ip: false,
+                    terminal: true,
+                },
+                HashPolicySpec {
+                    header: None,
+                    cookie: None,
+                    source_ip: true,
+                    terminal: false,
+                },
+            ],
+            ..Default::default()
+        };
+        let defaults = defaults("sessions", "sessions");
+        let policies = PoliciesSpec::default();
+        let value = route_from_spec(&route, &defaults, &policies, None);
+        let hp = value["route"]["hash_policy"].as_sequence().unwrap();
+        assert_eq!(hp.len(), 2);
+        assert_eq!(hp[0]["cookie"]["name"], Value::String("sticky".to_string()));
+        assert_eq!(hp[0]["cookie"]["ttl"], Value::String("3600s".to_string()));
+        assert_eq!(hp[0]["terminal"], Value::Bool(true));
+        assert_eq!(
+            hp[1]["connection_properties"]["source_ip"],
+            Value::Bool(true)
+        );
+    }
+
+    #[test]
+    fn test_gen_cluster_with_dns_options_and_eds() {
+        let mut upstream = UpstreamSpec {
+            name: "api".to_string(),
+            connect_timeout: "5s".to_string(),
+            r#type: ClusterType::LogicalDns,
+            lb_policy: LbPolicy::RoundRobin,
+            dns_lookup_family: Some(DnsLookupFamily::V4Only),
+            dns_refresh_rate: Some("30s".to_string()),
+            respect_dns_ttl: Some(true),
+            endpoints: vec![Endpoint {
+                address: "api.internal".to_string(),
+                port: 8080,
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let cluster = Value::Mapping(gen_cluster(&upstream));
+        assert_eq!(cluster["type"], Value::String("LOGICAL_DNS".to_string()));
+        assert_eq!(
+            cluster["dns_lookup_family"],
+            Value::String("V4_ONLY".to_string())
+        );
+        assert_eq!(
+            cluster["dns_refresh_rate"],
+            Value::String("30s".to_string())
+        );
+        assert_eq!(cluster["respect_dns_ttl"], Value::Bool(true));
+
+        upstream.r#type = ClusterType::Eds;
+        upstream.dns_lookup_family = None;
+        upstream.dns_refresh_rate = None;
+        upstream.respect_dns_ttl = None;
+        upstream.endpoints = vec![];
+        upstream.eds_config_path = Some("/etc/envoy/eds/api.yaml".to_string());
+        let cluster = Value::Mapping(gen_cluster(&upstream));
+        assert_eq!(cluster["type"], Value::String("EDS".to_string()));
+        assert_eq!(
+            cluster["eds_cluster_config"]["eds_config"]["path_config_source"]["path"],
+            Value::String("/etc/envoy/eds/api.yaml".to_string())
+        );
+        assert!(cluster.get("load_assignment").is_none());
+    }
+
+    #[test]
+    fn test_https_listener_with_cors_policies() {
+        let defaults = defaults("web", "web");
+        let log = stdout_log();
+        let mut cors = IndexMap::new();
+        cors.insert(
+            "spa".to_string(),
+            CorsPolicySpec {
+                allow_origins: vec![StringMatchSpec {
+                    exact: Some("https://app.example.com".to_string()),
+                    prefix: None,
+                    regex: None,
+                }],
+                allow_methods: vec!["GET".to_string(), "POST".to_string()],
+                allow_headers: vec![],
+                expose_headers: vec![],
+                max_age: Some(600),
+                allow_credentials: Some(true),
+            },
+        );
+        cors.insert(
+            "public".to_string(),
+            CorsPolicySpec {
+                allow_origins: vec![StringMatchSpec {
+                    exact: None,
+                    prefix: None,
+                    regex: Some(".*".to_string()),
+                }],
+                allow_methods: vec![],
+                allow_headers: vec![],
+                expose_headers: vec![],
+                max_age: None,
+                allow_credentials: None,
+            },
+        );
+        let policies = PoliciesSpec {
+            cors,
+            ..Default::default()
+        };
+        let domains = vec![DomainSpec {
+            domain: "example.com".to_string(),
+            mode: "terminate_https_443".to_string(),
+            tls: Some(TlsSpec {
+                cert_chain: Some("/path/to/cert".to_string()),
+                private_key: Some("/path/to/key".to_string()),
+                ..Default::default()
+            }),
+            routes: vec![RouteSpec {
+                m: MatchSpec {
+                    prefix: Some("/public".to_string()),
+                    ..Default::default()
+                },
+                to_upstream: Some("web".to_string()),
+                cors: Some("public".to_string()),
+                ..Default::default()
+            }],
+            cors: Some("spa".to_string()),
+            ..Default::default()
+        }];
+
+        let listener = Value::Mapping(gen_https_443_sni_listener(
+            &defaults,
+            &log,
+            None,
+            &domains,
+            &policies,
+            &[],
+        ));
+        let hcm = &listener["filter_chains"][0]["filters"][0]["typed_config"];
+        let filters = hcm["http_filters"].as_sequence().unwrap();
+        assert_eq!(
+            filters[0]["name"],
+            Value::String("envoy.filters.http.cors".to_string())
+        );
+        assert_eq!(
+            filters.last().unwrap()["name"],
+            Value::String("envoy.filters.http.router".to_string())
+        );
+
+        let vhost = &hcm["route_config"]["virtual_hosts"][0];
+        let vhost_cors = &vhost["typed_per_filter_config"]["envoy.filters.http.cors"];
+        assert_eq!(
+            vhost_cors["allow_origin_string_match"][0]["exact"],
+            Value::String("https://app.example.com".to_string())
+        );
+        assert_eq!(
+            vhost_cors["allow_methods"],
+            Value::String("GET,POST".to_string())
+        );
+        assert_eq!(vhost_cors["max_age"], Value::String("600".to_string()));
+        assert_eq!(vhost_cors["allow_credentials"], Value::Bool(true));
+
+        let route_cors = &vhost["routes"][0]["typed_per_filter_config"]["envoy.filters.http.cors"];
+        assert_eq!(
+            route_cors["allow_origin_string_match"][0]["safe_regex"]["regex"],
+            Value::String(".*".to_string())
+        );
+    }
+
+    #[test]
+    fn test_https_listener_with_access_policies() {
+        let defaults = defaults("web", "web");
+        let log = stdout_log();
+        let mut access = IndexMap::new();
+        access.insert(
+            "office_vpn".to_string(),
+            AccessPolicySpec {
+                action: RbacAction::Allow,
+                source_cidrs: vec!["10.8.0.0/16".to_string()],
+                except_cidrs: vec![],
+                headers: vec![],
+                enforce_l4: true,
+            },
+        );
+        access.insert(
+            "admins".to_string(),
+            AccessPolicySpec {
+                action: RbacAction::Allow,
+                source_cidrs: vec!["10.8.1.5".to_string()],
+                except_cidrs: vec!["10.8.1.0/24".to_string()],
+                headers: vec![HeaderMatcher {
+                    name: "x-admin".to_string(),
+                    exact_match: None,
+                }],
+                enforce_l4: false,
+            },
+        );
+        let policies = PoliciesSpec {
+            access,
+            ..Default::default()
+        };
+        let domains = vec![DomainSpec {
+            domain: "example.com".to_string(),
+            mode: "terminate_https_443".to_string(),
+            tls: Some(TlsSpec {
+                cert_chain: Some("/path/to/cert".to_string()),
+                private_key: Some("/path/to/key".to_string()),
+                ..Default::default()
+            }),
+            routes: vec![RouteSpec {
+                m: MatchSpec {
+                    prefix: Some("/internal/".to_string()),
+                    ..Default::default()
+                },
+                to_upstream: Some("web".to_string()),
+                access: Some("admins".to_string()),
+                ..Default::default()
+            }],
+            access: Some("office_vpn".to_string()),
+            ..Default::default()
+        }];
+
+        let listener = Value::Mapping(gen_https_443_sni_listener(
+            &defaults,
+            &log,
+            None,
+            &domains,
+            &policies,
+            &[],
+        ));
+        let chain = &listener["filter_chains"][0];
+        let ranges = &chain["filter_chain_match"]["source_prefix_ranges"];
+        assert_eq!(
+            ranges[0]["address_prefix"],
+            Value::String("10.8.0.0".to_string())
+        );
+        assert_eq!(ranges[0]["prefix_len"], Value::Number(16.into()));
+
+        let hcm = &chain["filters"][0]["typed_config"];
+        let filters = hcm["http_filters"].as_sequence().unwrap();
+        assert_eq!(
+            filters[0]["name"],
+            Value::String("envoy.filters.http.rbac".to_string())
+        );
+        let rules = &filters[0]["typed_config"]["rules"];
+        assert_eq!(rules["action"], Value::String("ALLOW".to_string()));
+        assert_eq!(
+            rules["policies"]["office_vpn"]["principals"][0]["and_ids"]["ids"][0]["or_ids"]["ids"]
+                [0]["remote_ip"]["address_prefix"],
+            Value::String("10.8.0.0".to_string())
+        );
+
+        let route = &hcm["route_config"]["virtual_hosts"][0]["routes"][0];
+        let ids = &route["typed_per_filter_config"]["envoy.filters.http.rbac"]["rbac"]["rules"]
+            ["policies"]["admins"]["principals"][0]["and_ids"]["ids"];
+        assert_eq!(
+            ids[0]["or_ids"]["ids"][0]["remote_ip"]["prefix_len"],
+            Value::Number(32.into())
+        );
+        assert_eq!(
+            ids[1]["not_id"]["or_ids"]["ids"][0]["remote_ip"]["prefix_len"],
+            Value::Number(24.into())
+        );
+        assert_eq!(ids[2]["header"]["present_match"], Value::Bool(true));
+    }
+
+    #[test]
+    fn test_rbac_rules_deny_policy_blocks_source_cidrs() {
+        let policy = AccessPolicySpec {
+            action: RbacAction::Deny,
+            source_cidrs: vec!["203.0.113.0/24".to_string()],
+            except_cidrs: vec!["203.0.113.7".to_string()],
+            headers: vec![],
+            enforce_l4: false,
+        };
+        let rules = rbac_rules("block_scrapers", &policy);
+        assert_eq!(rules["action"], Value::String("DENY".to_string()));
+
+        // DENY applies to the listed network itself, minus the exceptions
+        let ids = &rules["policies"]["block_scrapers"]["principals"][0]["and_ids"]["ids"];
+        let blocked = &ids[0]["or_ids"]["ids"][0]["remote_ip"];
+        assert_eq!(
+            blocked["address_prefix"],
+            Value::String("203.0.113.0".to_string())
+        );
+        assert_eq!(blocked["prefix_len"], Value::Number(24.into()));
+        assert!(ids[0].get("not_id").is_none());
+        let spared = &ids[1]["not_id"]["or_ids"]["ids"][0]["remote_ip"];
+        assert_eq!(
+            spared["address_prefix"],
+            Value::String("203.0.113.7".to_string())
+        );
+        assert_eq!(spared["prefix_len"], Value::Number(32.into()));
+        assert_eq!(ids.as_sequence().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_http_filters_with_jwt_authn() {
+        let mut jwt_providers = IndexMap::new();
+        jwt_providers.insert(
+            "auth0".to_string(),
+            JwtProviderSpec {
+                issuer: Some("https://example.eu.auth0.com/".to_string()),
+                audiences: vec!["api.example.com".to_string()],
+                local_jwks: None,
+                remote_jwks: Some(RemoteJwksSpec {
+                    uri: "https://example.eu.auth0.com/.well-known/jwks.json".to_string(),
+                    upstream: "auth0_jwks".to_string(),
+                    timeout: "5s".to_string(),
+                    cache_duration: None,
+                }),
+                forward: false,
+                forward_payload_header: None,
+                claim_to_headers: vec![ClaimToHeaderSpec {
+                    claim: "sub".to_string(),
+                    header: "x-user-id".to_string(),
+                }],
+            },
+        );
+        let policies = PoliciesSpec {
+            jwt_providers,
+            ..Default::default()
+        };
+        let domain = DomainSpec {
+            domain: "api.example.com".to_string(),
+            mode: "terminate_https_443".to_string(),
+            routes: vec![RouteSpec {
+                m: MatchSpec {
+                    prefix: Some("/search".to_string()),
+                    ..Default::default()
+                },
+                to_upstream: Some("api".to_string()),
+                jwt: Some(JwtRequirementSpec {
+                    provider: Some("auth0".to_string()),
+                    allow_missing: true,
+                    disabled: false,
+                    bypass_paths: vec![],
+                }),
+                ..Default::default()
+            }],
+            jwt: Some(JwtRequirementSpec {
+                provider: Some("auth0".to_string()),
+                allow_missing: false,
+                disabled: false,
+                bypass_paths: vec!["/health".to_string()],
+            }),
+            ..Default::default()
+        };
+
+        let filters = http_filters_for_domain(&domain, &policies);
+        assert_eq!(filters.len(), 2);
+        let jwt = &filters[0];
+        assert_eq!(
+            jwt["name"],
+            Value::String("envoy.filters.http.jwt_authn".to_string())
+        );
+        let tc = &jwt["typed_config"];
+        let provider = &tc["providers"]["auth0"];
+        assert_eq!(
+            provider["remote_jwks"]["http_uri"]["cluster"],
+            Value::String("auth0_jwks".to_string())
+        );
+        assert_eq!(
+            provider["claim_to_headers"][0]["header_name"],
+            Value::String("x-user-id".to_string())
+        );
+
+        let rules = tc["rules"].as_sequence().unwrap();
+        assert_eq!(rules.len(), 2);
+        assert_eq!(
+            rules[0]["match"]["prefix"],
+            Value::String("/health".to_string())
+        );
+        assert!(rules[0].get("requires").is_none());
+        assert_eq!(
+            rules[1]["requires"]["provider_name"],
+            Value::String("auth0".to_string())
+        );
+        assert_eq!(
+            tc["requirement_map"]["auth0_allow_missing"]["requires_any"]["requirements"][1]
+                ["allow_missing"],
+            Value::Mapping(Mapping::new())
+        );
+
+        let defaults = defaults("api", "api");
+        let route = route_from_spec(&domain.routes[0], &defaults, &policies, None);
+        assert_eq!(
+            route["typed_per_filter_config"]["envoy.filters.http.jwt_authn"]["requirement_name"],
+            Value::String("auth0_allow_missing".to_string())
+        );
+    }
+
+    #[test]
+    fn test_domain_local_ratelimit_with_descriptors_in_shadow_mode() {
+        let mut local_ratelimits = IndexMap::new();
+        local_ratelimits.insert(
+            "api".to_string(),
+            TokenBucket {
+                max_tokens: 100,
+                tokens_per_fill: 100,
+                fill_interval: "1s".to_string(),
+                filter_enforced: Some(0),
+                status: Some(503),
+                descriptors: vec![
+                    LocalRateLimitDescriptorSpec {
+                        header: Some("x-tenant".to_string()),
+                        remote_address: false,
+                        value: "free".to_string(),
+                        max_tokens: 10,
+                        tokens_per_fill: 10,
+                        fill_interval: "1s".to_string(),
+                    },
+                    LocalRateLimitDescriptorSpec {
+                        header: Some("x-tenant".to_string()),
+                        remote_address: false,
+                        value: "trial".to_string(),
+                        max_tokens: 5,
+                        tokens_per_fill: 5,
+                        fill_interval: "1s".to_string(),
+                    },
+                ],
+                ..Default::default()
+            },
+        );
+        let policies = PoliciesSpec {
+            local_ratelimits,
+            ..Default::default()
+        };
+        let domain = DomainSpec {
+            domain: "api.example.com".to_string(),
+            mode: "terminate_https_443".to_string(),
+            routes: vec![RouteSpec {
+                m: MatchSpec {
+                    prefix: Some("/".to_string()),
+                    ..Default::default()
+                },
+                to_upstream: Some("api".to_string()),
+                ..Default::default()
+            }],
+            local_ratelimit: Some("api".to_string()),
+            ..Default::default()
+        };
+
+        let filters = http_filters_for_domain(&domain, &policies);
+        let tc = &filters[0]["typed_config"];
+        assert_eq!(tc["stat_prefix"], Value::String("rl_api".to_string()));
+        assert_eq!(tc["token_bucket"]["max_tokens"], Value::Number(100.into()));
+        assert_eq!(
+            tc["filter_enabled"]["default_value"]["numerator"],
+            Value::Number(100.into())
+        );
+        assert_eq!(
+            tc["filter_enforced"]["default_value"]["numerator"],
+            Value::Number(0.into())
+        );
+        assert_eq!(tc["status"]["code"], Value::Number(503.into()));
+        assert_eq!(tc["stage"], Value::Number(1.into()));
+        let descriptors = tc["descriptors"].as_sequence().unwrap();
+        assert_eq!(descriptors.len(), 2);
+        assert_eq!(
+            descriptors[1]["entries"][0]["value"],
+            Value::String("trial".to_string())
+        );
+
+        let defaults = defaults("api", "api");
+        let route = route_from_spec(&domain.routes[0], &defaults, &policies, Some("api"));
+        // One action per descriptor key, in the local stage
+        let rate_limits = route["route"]["rate_limits"].as_sequence().unwrap();
+        assert_eq!(rate_limits.len(), 1);
+        assert_eq!(rate_limits[0]["stage"], Value::Number(1.into()));
+        assert_eq!(
+            rate_limits[0]["actions"][0]["request_headers"]["descriptor_key"],
+            Value::String("x-tenant".to_string())
+        );
+        assert!(route.get("typed_per_filter_config").is_none());
+    }
+
+    #[test]
+    fn test_listener_limits_on_edge_and_internal_listeners() {
+        let limits = ListenerLimitsSpec {
+            connection_rate_limit: Some(ConnectionRateLimitSpec {
+                max_tokens: 500,
+                tokens_per_fill: 100,
+                fill_interval: "1s".to_string(),
+            }),
+            max_connections: Some(10000),
+            per_connection_buffer_limit_bytes: Some(32768),
+        };
+        let log = stdout_log();
+        let internal = InternalHttpListenerSpec {
+            name: "internal_api".to_string(),
+            address: "127.0.0.1".to_string(),
+            port: 8080,
+            stat_prefix: "internal_api".to_string(),
+            domains: vec!["*".to_string()],
+            to_upstream: "web".to_string(),
+            limits: Some(ListenerLimitsSpec {
+                connection_rate_limit: None,
+                max_connections: Some(100),
+                per_connection_buffer_limit_bytes: None,
+            }),
+            ..Default::default()
+        };
+        let loaded = crate::load::Loaded {
+            defaults: DefaultsSpec {
+                listener_limits: limits,
+                ..defaults("web", "web")
+            },
+            listeners: ListenersSpec {
+                internal_http_listeners: vec![internal],
+            },
+            ..loaded()
+        };
+        let defaults = &loaded.defaults;
+
+        let http = Value::Mapping(gen_http_80_listener(defaults, &log, None));
+        assert_eq!(
+            http["per_connection_buffer_limit_bytes"],
+            Value::Number(32768.into())
+        );
+        assert!(http.get("listener_filters").is_none());
+        let filters = &http["filter_chains"][0]["filters"];
+        assert_eq!(
+            filters[0]["name"],
+            Value::String("envoy.filters.network.local_ratelimit".to_string())
+        );
+        assert_eq!(
+            filters[0]["typed_config"]["stat_prefix"],
+            Value::String("http_listener_conn_rl".to_string())
+        );
+        assert_eq!(
+            filters[0]["typed_config"]["token_bucket"]["max_tokens"],
+            Value::Number(500.into())
+        );
+        assert_eq!(
+            filters[1]["name"],
+            Value::String("envoy.filters.network.connection_limit".to_string())
+        );
+        assert_eq!(
+            filters[1]["typed_config"]["max_connections"],
+            Value::Number(10000.into())
+        );
+        assert_eq!(
+            filters[2]["name"],
+            Value::String("envoy.filters.network.http_connection_manager".to_string())
+        );
+
+        // Every :443 chain draws from the same token bucket
+        let domains = vec![DomainSpec {
+            domain: "example.com".to_string(),
+            mode: "terminate_https_443".to_string(),
+            tls: Some(TlsSpec {
+                cert_chain: Some("/path/to/cert".to_string()),
+                private_key: Some("/path/to/key".to_string()),
+                ..Default::default()
+            }),
+            routes: vec![RouteSpec {
+                m: MatchSpec {
+                    prefix: Some("/".to_string()),
+                    ..Default::default()
+                },
+                to_upstream: Some("web".to_string()),
+                ..Default::default()
+            }],
+            ..Default::default()
+        }];
+        let https = Value::Mapping(gen_https_443_sni_listener(
+            defaults,
+            &log,
+            None,
+            &domains,
+            &PoliciesSpec::default(),
+            &[],
+        ));
+        let chains = https["filter_chains"].as_sequence().unwrap();
+        assert_eq!(chains.len(), 2);
+        for (fc, last) in chains.iter().zip([
+            "envoy.filters.network.http_connection_manager",
+            "envoy.filters.network.tcp_proxy",
+        ]) {
+            let filters = fc["filters"].as_sequence().unwrap();
+            assert_eq!(filters.len(), 3);
+            assert_eq!(
+                filters[0]["typed_config"]["share_key"],
+                Value::String("https_sni_listener".to_string())
+            );
+            assert_eq!(
+                filters[1]["name"],
+                Value::String("envoy.filters.network.connection_limit".to_string())
+            );
+            assert_eq!(filters[2]["name"], Value::String(last.to_string()));
+        }
+
+        let internal = &loaded.listeners.internal_http_listeners[0];
+        let listener = Value::Mapping(gen_internal_http_listener(defaults, &log, None, internal));
+        assert!(listener.get("per_connection_buffer_limit_bytes").is_none());
+        let filters = &listener["filter_chains"][0]["filters"];
+        assert_eq!(
+            filters[0]["typed_config"]["stat_prefix"],
+            Value::String("internal_api_conn_limit".to_string())
+        );
+        assert_eq!(
+            filters[1]["name"],
+            Value::String("envoy.filters.network.http_connection_manager".to_string())
+        );
+
+        // connection_limit counts per chain; the runtime caps each listener
+        let caps = listener_connection_limits(&loaded);
+        let runtime = gen_layered_runtime(None, &caps);
+        let per_listener =
+            &runtime["layers"][0]["static_layer"]["envoy"]["resource_limits"]["listener"];
+        assert_eq!(
+            per_listener["http_listener"]["connection_limit"],
+            Value::Number(10000.into())
+        );
+        assert_eq!(
+            per_listener["https_sni_listener"]["connection_limit"],
+            Value::Number(10000.into())
+        );
+        assert_eq!(
+            per_listener["internal_api"]["connection_limit"],
+            Value::Number(100.into())
+        );
+        assert!(runtime["layers"][0]["static_layer"]
+            .get("overload")
+            .is_none());
+    }
+
+    #[test]
+    fn test_websocket_and_connect_upgrades() {
+        let defaults = defaults("chat", "chat");
+        let websocket = RouteSpec {
+            m: MatchSpec {
+                prefix: Some("/ws/".to_string()),
+                ..Default::default()
+            },
+            to_upstream: Some("chat".to_string()),
+            timeout: Some("0s".to_string()),
+            websocket: true,
+            ..Default::default()
+        };
+        let route = route_from_spec(&websocket, &defaults, &PoliciesSpec::default(), None);
+        assert_eq!(route["match"]["prefix"], Value::String("/ws/".to_string()));
+        let upgrade = &route["route"]["upgrade_configs"][0];
+        assert_eq!(
+            upgrade["upgrade_type"],
+            Value::String("websocket".to_string())
+        );
+        assert!(upgrade.get("connect_config").is_none());
+        assert_eq!(route["route"]["timeout"], Value::String("0s".to_string()));
+
+        let tunnel = RouteSpec {
+            to_upstream: Some("tunnel".to_string()),
+            connect: true,
+            ..Default::default()
+        };
+        let route = route_from_spec(&tunnel, &defaults, &PoliciesSpec::default(), None);
+        assert_eq!(
+            route["match"]["connect_matcher"],
+            Value::Mapping(Mapping::new())
+        );
+        let upgrade = &route["route"]["upgrade_configs"][0];
+        assert_eq!(
+            upgrade["upgrade_type"],
+            Value::String("CONNECT".to_string())
+        );
+        assert_eq!(upgrade["connect_config"], Value::Mapping(Mapping::new()));
+
+        let hcm_spec: HttpConnectionManagerSpec = serde_yaml::from_str("websocket: true").unwrap();
+        let log: AccessLogSpec = serde_yaml::from_str("{}").unwrap();
+        let hcm = http_connection_manager(
+            "chat",
+            &log,
+            Value::Mapping(Mapping::new()),
+            vec![http_filter_router()],
+            Some(&hcm_spec),
+            None,
+        );
+        assert_eq!(
+            hcm["upgrade_configs"][0]["upgrade_type"],
+            Value::String("websocket".to_string())
+        );
+    }
+
+    #[test]
+    fn test_grpc_route_upstream_preset_and_stats() {
+        let defaults = defaults("users", "users");
+        let grpc = RouteSpec {
+            m: MatchSpec {
+                prefix: Some("/users.v1.UserService/".to_string()),
+                grpc: true,
+                ..Default::default()
+            },
+            to_upstream: Some("users".to_string()),
+            max_grpc_timeout: Some("30s".to_string()),
+            grpc_timeout_header_offset: Some("0.05s".to_string()),
+            ..Default::default()
+        };
+        let route = route_from_spec(&grpc, &defaults, &PoliciesSpec::default(), None);
+        assert_eq!(route["match"]["grpc"], Value::Mapping(Mapping::new()));
+        let msd = &route["route"]["max_stream_duration"];
+        assert_eq!(
+            msd["grpc_timeout_header_max"],
+            Value::String("30s".to_string())
+        );
+        assert_eq!(
+            msd["grpc_timeout_header_offset"],
+            Value::String("0.05s".to_string())
+        );
+
+        let upstream: UpstreamSpec = serde_yaml::from_str(
+            "name: users\nendpoints:\n  - { address: users, port: 50051 }\npreset: grpc\n",
+        )
+        .unwrap();
+        let cluster = gen_cluster(&upstream);
+        let keepalive = &cluster[&s("http2_protocol_options")]["connection_keepalive"];
+        assert_eq!(keepalive["interval"], Value::String("30s".to_string()));
+        assert_eq!(keepalive["timeout"], Value::String("5s".to_string()));
+
+        let stats = http_filter_grpc_stats();
+        assert_eq!(
+            stats["name"],
+            Value::String("envoy.filters.http.grpc_stats".to_string())
+        );
+        assert_eq!(
+            stats["typed_config"]["stats_for_all_methods"],
+            Value::Bool(true)
+        );
+    }
+
+    #[test]
+    fn test_global_ratelimit_filter_and_route_actions() {
+        let mut global_ratelimits = IndexMap::new();
+        global_ratelimits.insert(
+            "per_api_key".to_string(),
+            GlobalRateLimitSpec {
+                actions: vec![
+                    RateLimitActionSpec {
+                        remote_address: true,
+                        request_headers: None,
+                        generic_key: None,
+                    },
+                    RateLimitActionSpec {
+                        remote_address: false,
+                        request_headers: Some(RequestHeadersActionSpec {
+                            header_name: "x-api-key".to_string(),
+                            descriptor_key: "api_key".to_string(),
+                            skip_if_absent: true,
+                        }),
+                        generic_key: None,
+                    },
+                ],
+            },
+        );
+        let policies = PoliciesSpec {
+            global_ratelimits,
+            ratelimit_service: Some(RateLimitServiceSpec {
+                upstream: "ratelimit".to_string(),
+                domain: "edge".to_string(),
+                timeout: "20ms".to_string(),
+                failure_mode_deny: true,
+            }),
+            ..Default::default()
+        };
+        let domain = DomainSpec {
+            domain: "api.example.com".to_string(),
+            mode: "terminate_https_443".to_string(),
+            routes: vec![RouteSpec {
+                m: MatchSpec {
+                    prefix: Some("/v1/".to_string()),
+                    ..Default::default()
+                },
+                to_upstream: Some("api".to_string()),
+                per_filter_config: Some(PerFilterConfigRef {
+                    global_ratelimits: vec!["per_api_key".to_string()],
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        let filters = http_filters_for_domain(&domain, &policies);
+        assert_eq!(filters.len(), 2);
+        let tc = &filters[0]["typed_config"];
+        assert_eq!(
+            filters[0]["name"],
+            Value::String("envoy.filters.http.ratelimit".to_string())
+        );
+        assert_eq!(tc["domain"], Value::String("edge".to_string()));
+        assert_eq!(tc["failure_mode_deny"], Value::Bool(true));
+        assert_eq!(
+            tc["rate_limit_service"]["grpc_service"]["envoy_grpc"]["cluster_name"],
+            Value::String("ratelimit".to_string())
+        );
+
+        let defaults = defaults("api", "api");
+        let route = route_from_spec(&domain.routes[0], &defaults, &policies, None);
+        let actions = &route["route"]["rate_limits"][0]["actions"];
+        assert_eq!(actions[0]["remote_address"], Value::Mapping(Mapping::new()));
+        assert_eq!(
+            actions[1]["request_headers"]["descriptor_key"],
+            Value::String("api_key".to_string())
+        );
+        assert_eq!(
+            actions[1]["request_headers"]["skip_if_absent"],
+            Value::Bool(true)
+        );
+    }
+
+    #[test]
+    fn test_http_filter_ext_authz_modes() {
+        let mut policy = ExtAuthzSpec {
+            mode: ExtAuthzMode::Http,
+            upstream: "oauth2_proxy".to_string(),
+            timeout: "1s".to_string(),
+            failure_mode_allow: false,
+            path_prefix: Some("/oauth2/auth".to_string()),
+            allowed_request_headers: vec!["cookie".to_string()],
+            allowed_upstream_headers: vec!["x-auth-request-user".to_string()],
+        };
+        let tc = &http_filter_ext_authz(&policy)["typed_config"];
+        let svc = &tc["http_service"];
+        assert_eq!(
+            svc["server_uri"]["cluster"],
+            Value::String("oauth2_proxy".to_string())
+        );
+        assert_eq!(
+            svc["path_prefix"],
+            Value::String("/oauth2/auth".to_string())
+        );
+        assert_eq!(
+            tc["allowed_headers"]["patterns"][0]["exact"],
+            Value::String("cookie".to_string())
+        );
+        assert!(svc.get("authorization_request").is_none());
+        assert_eq!(
+            svc["authorization_response"]["allowed_upstream_headers"]["patterns"][0]["exact"],
+            Value::String("x-auth-request-user".to_string())
+        );
+        assert_eq!(tc["failure_mode_allow"], Value::Bool(false));
+
+        policy.mode = ExtAuthzMode::Grpc;
+        policy.path_prefix = None;
+        policy.allowed_upstream_headers = vec![];
+        let tc = &http_filter_ext_authz(&policy)["typed_config"];
+        assert!(tc.get("http_service").is_none());
+        assert_eq!(
+            tc["grpc_service"]["envoy_grpc"]["cluster_name"],
+            Value::String("oauth2_proxy".to_string())
+        );
+        assert_eq!(
+            tc["allowed_headers"]["patterns"][0]["exact"],
+            Value::String("cookie".to_string())
+        );
+    }
+
+    #[test]
+    fn test_http_filter_compressor() {
+        let gzip = CompressionPolicySpec {
+            algorithm: CompressionAlgorithm::Gzip,
+            min_content_length: Some(1024),
+            content_types: vec!["application/json".to_string()],
+            level: Some(6),
+        };
+        let f = http_filter_compressor("gzip_json", &gzip);
+        assert_eq!(
+            f["name"],
+            Value::String("envoy.filters.http.compressor.gzip_json".to_string())
+        );
+        let tc = &f["typed_config"];
+        let common = &tc["response_direction_config"]["common_config"];
+        assert_eq!(common["min_content_length"], Value::Number(1024.into()));
+        assert_eq!(
+            common["content_type"][0],
+            Value::String("application/json".to_string())
+        );
+        let library = &tc["compressor_library"]["typed_config"];
+        assert_eq!(
+            library["compression_level"],
+            Value::String("COMPRESSION_LEVEL_6".to_string())
+        );
+
+        let brotli = CompressionPolicySpec {
+            algorithm: CompressionAlgorithm::Brotli,
+            min_content_length: None,
+            content_types: vec![],
+            level: Some(5),
+        };
+        let f = http_filter_compressor("br", &brotli);
+        let library = &f["typed_config"]["compressor_library"]["typed_config"];
+        assert_eq!(
+            library["@type"],
+            Value::String(
+                "type.googleapis.com/envoy.extensions.compression.brotli.compressor.v3.Brotli"
+                    .to_string()
+            )
+        );
+        assert_eq!(library["quality"], Value::Number(5.into()));
+        assert!(
+            f["typed_config"]["response_direction_config"]["common_config"]
+                .get("content_type")
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_access_log_formats_and_filters() {
+        let mut json = IndexMap::new();
+        json.insert("status".to_string(), "%RESPONSE_CODE%".to_string());
+        let log = AccessLogSpec {
+            json_format: Some(json),
+            filter: Some(AccessLogFilterSpec {
+                status_codes: vec![
+                    StatusCodeRangeSpec {
+                        min: Some(400),
+                        max: Some(499),
+                    },
+                    StatusCodeRangeSpec {
+                        min: Some(500),
+                        max: None,
+                    },
+                ],
+                sample_percent: None,
+                not_health_check: true,
+            }),
+            ..stdout_log()
+        };
+        let entry = &access_log(&log, "web")[0];
+        assert_eq!(
+            entry["name"],
+            Value::String("envoy.access_loggers.stdout".to_string())
+        );
+        assert!(entry["typed_config"].get("path").is_none());
+        assert_eq!(
+            entry["typed_config"]["log_format"]["json_format"]["status"],
+            Value::String("%RESPONSE_CODE%".to_string())
+        );
+        let all = &entry["filter"]["and_filter"]["filters"];
+        let ranges = &all[0]["or_filter"]["filters"];
+        let client_errors = &ranges[0]["and_filter"]["filters"];
+        assert_eq!(
+            client_errors[1]["status_code_filter"]["comparison"]["op"],
+            Value::String("LE".to_string())
+        );
+        assert_eq!(
+            ranges[1]["status_code_filter"]["comparison"]["value"]["default_value"],
+            Value::Number(500.into())
+        );
+        assert_eq!(
+            client_errors[0]["status_code_filter"]["comparison"]["value"]["runtime_key"],
+            Value::String("access_log.web.status_code_0_ge".to_string())
+        );
+        assert_eq!(
+            ranges[1]["status_code_filter"]["comparison"]["value"]["runtime_key"],
+            Value::String("access_log.web.status_code_1_ge".to_string())
+        );
+        assert!(all[1].get("not_health_check_filter").is_some());
+
+        let log = AccessLogSpec {
+            r#type: AccessLogType::File,
+            path: "/var/log/envoy/access.log".to_string(),
+            format: Some("%START_TIME% %RESPONSE_CODE%".to_string()),
+            filter: Some(AccessLogFilterSpec {
+                status_codes: vec![],
+                sample_percent: Some(25),
+                not_health_check: false,
+            }),
+            ..Default::default()
+        };
+        let entry = &access_log(&log, "web")[0];
+        assert_eq!(
+            entry["typed_config"]["path"],
+            Value::String("/var/log/envoy/access.log".to_string())
+        );
+        assert_eq!(
+            entry["typed_config"]["log_format"]["text_format_source"]["inline_string"],
+            Value::String("%START_TIME% %RESPONSE_CODE%\n".to_string())
+        );
+        assert_eq!(
+            entry["filter"]["runtime_filter"]["percent_sampled"]["numerator"],
+            Value::Number(25.into())
+        );
+
+        // Every log gets its own runtime keys
+        let sampled = log.filter.clone();
+        let log = AccessLogSpec {
+            sinks: vec![AccessLogSpec {
+                filter: sampled,
+                ..stdout_log()
+            }],
+            ..log
+        };
+        let entries = access_log(&log, "example_com");
+        assert_eq!(
+            entries[0]["filter"]["runtime_filter"]["runtime_key"],
+            Value::String("access_log.example_com.sample_percent".to_string())
+        );
+        assert_eq!(
+            entries[1]["filter"]["runtime_filter"]["runtime_key"],
+            Value::String("access_log.example_com.sink_1.sample_percent".to_string())
+        );
+    }
+
+    #[test]
+    fn test_tracing_on_connection_managers_with_domain_overrides() {
+        let mut tracing = TracingSpec {
+            provider: TracingProvider::OpenTelemetry,
+            upstream: "otel_collector".to_string(),
+            service_name: "edge".to_string(),
+            collector_endpoint: None,
+            sampling: TracingSamplingSpec {
+                random_sampling: Some(10.0),
+                client_sampling: None,
+                overall_sampling: None,
+            },
+            custom_tags: vec![CustomTagSpec {
+                tag: "env".to_string(),
+                literal: Some("production".to_string()),
+                request_header: None,
+                environment: None,
+            }],
+        };
+        let defaults = defaults("web", "web");
+        let log: AccessLogSpec = serde_yaml::from_str("{}").unwrap();
+
+        let listener = Value::Mapping(gen_http_80_listener(&defaults, &log, Some(&tracing)));
+        let hcm = &listener["filter_chains"][0]["filters"][0]["typed_config"];
+        let provider = &hcm["tracing"]["provider"];
+        assert_eq!(
+            provider["name"],
+            Value::String("envoy.tracers.opentelemetry".to_string())
+        );
+        assert_eq!(
+            provider["typed_config"]["grpc_service"]["envoy_grpc"]["cluster_name"],
+            Value::String("otel_collector".to_string())
+        );
+        assert_eq!(
+            provider["typed_config"]["service_name"],
+            Value::String("edge".to_string())
+        );
+        assert_eq!(
+            hcm["tracing"]["random_sampling"]["value"],
+            Value::from(10.0)
+        );
+
+        let domain = DomainTracingSpec {
+            sampling: TracingSamplingSpec {
+                random_sampling: Some(100.0),
+                client_sampling: None,
+                overall_sampling: Some(50.0),
+            },
+            custom_tags: vec![CustomTagSpec {
+                tag: "tenant".to_string(),
+                literal: None,
+                request_header: Some("x-tenant-id".to_string()),
+                environment: None,
+            }],
+        };
+        let config = tracing_config(&tracing, Some(&domain));
+        assert_eq!(config["random_sampling"]["value"], Value::from(100.0));
+        assert_eq!(config["overall_sampling"]["value"], Value::from(50.0));
+        assert!(config.get("client_sampling").is_none());
+        let tags = config["custom_tags"].as_sequence().unwrap();
+        assert_eq!(tags.len(), 2);
+        assert_eq!(
+            tags[0]["literal"]["value"],
+            Value::String("production".to_string())
+        );
+        assert_eq!(
+            tags[1]["request_header"]["name"],
+            Value::String("x-tenant-id".to_string())
+        );
+
+        tracing.provider = TracingProvider::Zipkin;
+        let provider = &tracing_config(&tracing, None)["provider"];
+        assert_eq!(
+            provider["name"],
+            Value::String("envoy.tracers.zipkin".to_string())
+        );
+        assert_eq!(
+            provider["typed_config"]["collector_endpoint"],
+            Value::String("/api/v2/spans".to_string())
+        );
+    }
+
+    #[test]
+    fn test_stats_config_and_sinks() {
+        let stats = StatsSpec {
+            sinks: vec![
+                StatsSinkSpec {
+                    r#type: StatsSinkType::Dogstatsd,
+                    address: Some("127.0.0.1".to_string()),
+                    port: Some(8125),
+                    upstream: None,
+                    prefix: Some("envoy".to_string()),
+                },
+                StatsSinkSpec {
+                    r#type: StatsSinkType::Statsd,
+                    address: None,
+                    port: None,
+                    upstream: Some("statsd_exporter".to_string()),
+                    prefix: None,
+                },
+            ],
+            tags: vec![StatsTagSpec {
+                tag_name: "region".to_string(),
+                regex: None,
+                fixed_value: Some("eu-west-1".to_string()),
+            }],
+            use_all_default_tags: Some(true),
+            include: vec![],
+            exclude: vec![StringMatchSpec {
+                exact: None,
+                prefix: Some("cluster.default_".to_string()),
+                regex: None,
+            }],
+        };
+
+        let config = gen_stats_config(&stats).unwrap();
+        assert_eq!(
+            config["stats_tags"][0]["fixed_value"],
+            Value::String("eu-west-1".to_string())
+        );
+        assert_eq!(config["use_all_default_tags"], Value::Bool(true));
+        assert_eq!(
+            config["stats_matcher"]["exclusion_list"]["patterns"][0]["prefix"],
+            Value::String("cluster.default_".to_string())
+        );
+        assert!(gen_stats_config(&StatsSpec::default()).is_none());
+
+        let dog = stats_sink(&stats.sinks[0]);
+        assert_eq!(
+            dog["name"],
+            Value::String("envoy.stat_sinks.dog_statsd".to_string())
+        );
+        let address = &dog["typed_config"]["address"]["socket_address"];
+        assert_eq!(address["protocol"], Value::String("UDP".to_string()));
+        assert_eq!(address["port_value"], Value::Number(8125.into()));
+        assert_eq!(
+            dog["typed_config"]["prefix"],
+            Value::String("envoy".to_string())
+        );
+
+        let statsd = stats_sink(&stats.sinks[1]);
+        assert_eq!(
+            statsd["typed_config"]["tcp_cluster_name"],
+            Value::String("statsd_exporter".to_string())
+        );
+        assert!(statsd["typed_config"].get("address").is_none());
+    }
+
+    #[test]
+    fn test_admin_socket_and_readonly_listener() {
+        let log: AccessLogSpec = serde_yaml::from_str("{}").unwrap();
+        let mut admin = AdminSpec {
+            address: "0.0.0.0".to_string(),
+            port: 9901,
+            profile_path: Some("/var/lib/envoy/envoy.prof".to_string()),
+            access_log: Some(serde_yaml::from_str("{ type: stderr }").unwrap()),
+            readonly_listener: Some(AdminReadonlyListenerSpec {
+                address: "0.0.0.0".to_string(),
+                port: 9902,
+            }),
+            ..Default::default()
+        };
+        let out = gen_admin(&admin, &log);
+        assert_eq!(
+            out["access_log"][0]["name"],
+            Value::String("envoy.access_loggers.stderr".to_string())
+        );
+        assert_eq!(
+            out["profile_path"],
+            Value::String("/var/lib/envoy/envoy.prof".to_string())
+        );
+
+        let cluster = Value::Mapping(gen_admin_cluster(&admin));
+        let address = &cluster["load_assignment"]["endpoints"][0]["lb_endpoints"][0]["endpoint"]
+            ["address"]["socket_address"];
+        assert_eq!(address["address"], Value::String("127.0.0.1".to_string()));
+        assert_eq!(address["port_value"], Value::Number(9901.into()));
+
+        admin.socket_path = Some("/var/run/envoy/admin.sock".to_string());
+        let out = gen_admin(&admin, &log);
+        assert_eq!(
+            out["address"]["pipe"]["path"],
+            Value::String("/var/run/envoy/admin.sock".to_string())
+        );
+        let cluster = Value::Mapping(gen_admin_cluster(&admin));
+        assert_eq!(
+            cluster["load_assignment"]["endpoints"][0]["lb_endpoints"][0]["endpoint"]["address"]
+                ["pipe"]["path"],
+            Value::String("/var/run/envoy/admin.sock".to_string())
+        );
+
+        let listener = Value::Mapping(gen_admin_readonly_listener(
+            admin.readonly_listener.as_ref().unwrap(),
+            &log,
+        ));
+        assert_eq!(
+            listener["address"]["socket_address"]["port_value"],
+            Value::Number(9902.into())
+        );
+        let routes = &listener["filter_chains"][0]["filters"][0]["typed_config"]["route_config"]
+            ["virtual_hosts"][0]["routes"];
+        let paths: Vec<_> = routes
+            .as_sequence()
+            .unwrap()
+            .iter()
+            .map(|r| r["match"]["path"].as_str().unwrap())
+            .collect();
+        assert_eq!(paths, vec!["/ready", "/stats/prometheus"]);
+        assert_eq!(
+            routes[0]["route"]["cluster"],
+            Value::String("envoy_admin".to_string())
+        );
+    }
+
+    #[test]
+    fn test_overload_manager_and_layered_runtime() {
+        assert!(gen_overload_manager(&OverloadSpec::default()).is_none());
+
+        let overload = OverloadSpec {
+            refresh_interval: Some("0.25s".to_string()),
+            max_heap_size_bytes: Some(1_073_741_824),
+            max_active_downstream_connections: Some(50_000),
+            global_downstream_max_connections: None,
+            actions: vec![
+                OverloadActionSpec {
+                    action: OverloadAction::ShrinkHeap,
+                    heap_threshold: 0.9,
+                },
+                OverloadActionSpec {
+                    action: OverloadAction::DisableHttpKeepalive,
+                    heap_threshold: 0.95,
+                },
+            ],
+        };
+        let manager = gen_overload_manager(&overload).unwrap();
+        assert_eq!(
+            manager["refresh_interval"],
+            Value::String("0.25s".to_string())
+        );
+        let monitors = &manager["resource_monitors"];
+        assert_eq!(
+            monitors[0]["typed_config"]["max_heap_size_bytes"],
+            Value::Number(1_073_741_824u64.into())
+        );
+        assert_eq!(
+            monitors[1]["name"],
+            Value::String("envoy.resource_monitors.global_downstream_max_connections".to_string())
+        );
+        let action = &manager["actions"][1];
+        assert_eq!(
+            action["name"],
+            Value::String("envoy.overload_actions.disable_http_keepalive".to_string())
+        );
+        assert_eq!(
+            action["triggers"][0]["name"],
+            Value::String("envoy.resource_monitors.fixed_heap".to_string())
+        );
+        assert_eq!(
+            action["triggers"][0]["threshold"]["value"],
+            Value::from(0.95)
+        );
+
+        let runtime = gen_layered_runtime(Some(20_000), &[]);
+        assert_eq!(
+            runtime["layers"][0]["static_layer"]["overload"]["global_downstream_max_connections"],
+            Value::Number(20_000.into())
+        );
+        assert_eq!(
+            runtime["layers"][1]["admin_layer"],
+            Value::Mapping(Mapping::new())
+        );
+    }
+
+    #[test]
+    fn test_builtin_listener_addresses() {
+        let mut listener = Mapping::new();
+        insert_builtin_addresses(&mut listener, &BuiltinListenerSpec::default(), 80);
+        assert_eq!(
+            listener["address"]["socket_address"]["address"],
+            Value::String("0.0.0.0".to_string())
+        );
+        assert_eq!(
+            listener["address"]["socket_address"]["port_value"],
+            Value::Number(80.into())
+        );
+        assert!(listener.get("additional_addresses").is_none());
+
+        let spec = BuiltinListenerSpec {
+            enabled: true,
+            address: "::".to_string(),
+            port: Some(8443),
+            ipv4_compat: true,
+            additional_addresses: vec![
+                ListenerAddressSpec {
+                    address: "10.0.0.5".to_string(),
+                    port: None,
+                },
+                ListenerAddressSpec {
+                    address: "10.0.0.6".to_string(),
+                    port: Some(443),
+                },
+            ],
+        };
+        let mut listener = Mapping::new();
+        insert_builtin_addresses(&mut listener, &spec, 8443);
+        let main = &listener["address"]["socket_address"];
+        assert_eq!(main["address"], Value::String("::".to_string()));
+        assert_eq!(main["port_value"], Value::Number(8443.into()));
+        assert_eq!(main["ipv4_compat"], Value::Bool(true));
+        let extra = &listener["additional_addresses"];
+        assert_eq!(
+            extra[0]["address"]["socket_address"]["port_value"],
+            Value::Number(8443.into())
+        );
+        assert_eq!(
+            extra[1]["address"]["socket_address"]["address"],
+            Value::String("10.0.0.6".to_string())
+        );
+        assert_eq!(
+            extra[1]["address"]["socket_address"]["port_value"],
+            Value::Number(443.into())
+        );
+        assert!(extra[0]["address"]["socket_address"]
+            .get("ipv4_compat")
+            .is_none());
+    }
+
+    #[test]
+    fn test_access_log_with_remote_sinks() {
+        let mut json = IndexMap::new();
+        json.insert("status".to_string(), "%RESPONSE_CODE%".to_string());
+        let log = AccessLogSpec {
+            sinks: vec![
+                AccessLogSpec {
+                    r#type: AccessLogType::OpenTelemetry,
+                    path: "/dev/stdout".to_string(),
+                    format: Some("%REQ(:PATH)%".to_string()),
+                    json_format: Some(json),
+                    upstream: Some("otel_collector".to_string()),
+                    log_name: Some("edge".to_string()),
+                    ..Default::default()
+                },
+                AccessLogSpec {
+                    r#type: AccessLogType::Grpc,
+                    path: "/dev/stdout".to_string(),
+                    upstream: Some("als".to_string()),
+                    ..Default::default()
+                },
+            ],
+            ..stdout_log()
+        };
+        let entries = access_log(&log, "web");
+        assert_eq!(entries.as_sequence().unwrap().len(), 3);
+        assert_eq!(
+            entries[0]["name"],
+            Value::String("envoy.access_loggers.stdout".to_string())
+        );
+
+        let otel = &entries[1]["typed_config"];
+        assert_eq!(
+            otel["common_config"]["grpc_service"]["envoy_grpc"]["cluster_name"],
+            Value::String("otel_collector".to_string())
+        );
+        assert_eq!(
+            otel["common_config"]["log_name"],
+            Value::String("edge".to_string())
+        );
+        assert_eq!(
+            otel["body"]["string_value"],
+            Value::String("%REQ(:PATH)%".to_string())
+        );
+        assert_eq!(
+            otel["attributes"]["values"][0]["key"],
+            Value::String("status".to_string())
+        );
+        assert!(otel.get("log_format").is_none());
+
+        let als = &entries[2];
+        assert_eq!(
+            als["name"],
+            Value::String("envoy.access_loggers.http_grpc".to_string())
+        );
+        assert_eq!(
+            als["typed_config"]["common_config"]["log_name"],
+            Value::String("envoy_access_log".to_string())
+        );
+        assert_eq!(
+            als["typed_config"]["common_config"]["transport_api_version"],
+            Value::String("V3".to_string())
+        );
+    }
+
+    #[test]
+    fn test_downstream_tls_socket_with_client_verification() {
+        let tls = TlsSpec {
+            cert_chain: Some("/path/to/cert".to_string()),
+            private_key: Some("/path/to/key".to_string()),
+            client_ca: Some("/path/to/ca.pem".to_string()),
+            require_client_certificate: true,
+            verify_san: vec!["client.partner.com".to_string()],
+            verify_hash: vec!["abcdef".to_string()],
+            crl: Some("/path/to/ca.crl".to_string()),
+            ..Default::default()
+        };
+
+        let tc = &downstream_tls_socket(&tls, &TlsParamsSpec::default(), &[])["typed_config"];
+        assert_eq!(tc["require_client_certificate"], Value::Bool(true));
+        let vc = &tc["common_tls_context"]["validation_context"];
+        assert_eq!(
+            vc["trusted_ca"]["filename"],
+            Value::String("/path/to/ca.pem".into())
+        );
+        assert_eq!(
+            vc["match_typed_subject_alt_names"][0]["matcher"]["exact"],
+            Value::String("client.partner.com".into())
+        );
+        assert_eq!(
+            vc["verify_certificate_hash"][0],
+            Value::String("abcdef".into())
+        );
+        assert!(vc.get("verify_certificate_spki").is_none());
+        assert_eq!(
+            vc["crl"]["filename"],
+            Value::String("/path/to/ca.crl".into())
+        );
+    }
+
+    #[test]
+    fn test_downstream_tls_socket_without_client_ca() {
+        let tls = TlsSpec {
+            cert_chain: Some("/path/to/cert".to_string()),
+            private_key: Some("/path/to/key".to_string()),
+            ..Default::default()
+        };
+
+        let tc = &downstream_tls_socket(&tls, &TlsParamsSpec::default(), &[])["typed_config"];
+        assert!(tc.get("require_client_certificate").is_none());
+        assert!(tc["common_tls_context"].get("validation_context").is_none());
+        assert_eq!(
+            tc["common_tls_context"]["tls_certificates"][0]["private_key"]["filename"],
+            Value::String("/path/to/key".into())
+        );
+    }
+
+    #[test]
+    fn test_downstream_tls_socket_with_profile_defaults() {
+        let tls = TlsSpec {
+            cert_chain: Some("/path/to/cert".to_string()),
+            private_key: Some("/path/to/key".to_string()),
+            params: TlsParamsSpec {
+                alpn_protocols: Some(vec!["h2".to_string(), "http/1.1".to_string()]),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let defaults = TlsParamsSpec {
+            profile: Some(TlsProfile::Intermediate),
+            ..Default::default()
+        };
+
+        let ctc =
+            &downstream_tls_socket(&tls, &defaults, &[])["typed_config"]["common_tls_context"];
+        let params = &ctc["tls_params"];
+        assert_eq!(
+            params["tls_minimum_protocol_version"],
+            Value::String("TLSv1_2".into())
+        );
+        assert_eq!(
+            params["tls_maximum_protocol_version"],
+            Value::String("TLSv1_3".into())
+        );
+        assert_eq!(params["cipher_suites"].as_sequence().unwrap().len(), 6);
+        assert_eq!(ctc["alpn_protocols"][0], Value::String("h2".into()));
+    }
+
+    #[test]
+    fn test_downstream_tls_socket_with_multiple_certificates() {
+        let tls = TlsSpec {
+            certificates: vec![CertificateSpec {
+                cert_chain: "/certs/ecdsa.pem".to_string(),
+                private_key: "/certs/ecdsa.key".to_string(),
+                ocsp_staple: Some("/certs/ecdsa.ocsp".to_string()),
+            }],
+            cert_set: Some("wildcard".to_string()),
+            ..Default::default()
+        };
+        let cert_sets = vec![CertSetSpec {
+            name: "wildcard".to_string(),
+            certificates: vec![CertificateSpec {
+                cert_chain: "/certs/wildcard.pem".to_string(),
+                private_key: "/certs/wildcard.key".to_string(),
+                ocsp_staple: None,
+            }],
+        }];
+
+        let socket = downstream_tls_socket(&tls, &TlsParamsSpec::default(), &cert_sets);
+        let certs = socket["typed_config"]["common_tls_context"]["tls_certificates"]
+            .as_sequence()
+            .unwrap();
+        assert_eq!(certs.len(), 2);
+        assert_eq!(
+            certs[0]["ocsp_staple"]["filename"],
+            Value::String("/certs/ecdsa.ocsp".into())
+        );
+        assert_eq!(
+            certs[1]["certificate_chain"]["filename"],
+            Value::String("/certs/wildcard.pem".into())
+        );
+        assert!(certs[1].get("ocsp_staple").is_none());
+    }
+
     #[test]
     fn test_match_to_value() {
         // Test prefix match
@@ -1076,12 +4764,13 @@ mod tests {
             prefix: Some("/api".to_string()),
             path: None,
             headers: vec![],
+            ..Default::default()
         };
         let result = match_to_value(&prefix_match);
         match &result {
             Value::Mapping(m) => {
                 assert_eq!(
-                    m.get(&Value::String("prefix".to_string())).unwrap(),
+                    m.get(Value::String("prefix".to_string())).unwrap(),
                     &Value::String("/api".to_string())
                 );
             }
@@ -1093,12 +4782,13 @@ mod tests {
             prefix: None,
             path: Some("/exact/path".to_string()),
             headers: vec![],
+            ..Default::default()
         };
         let result = match_to_value(&path_match);
         match &result {
             Value::Mapping(m) => {
                 assert_eq!(
-                    m.get(&Value::String("path".to_string())).unwrap(),
+                    m.get(Value::String("path".to_string())).unwrap(),
                     &Value::String("/exact/path".to_string())
                 );
             }
@@ -4,6 +4,8 @@ pub mod generate;
 pub mod init;
 pub mod load;
 pub mod model;
+#[cfg(test)]
+mod test_fixtures;
 pub mod validate;
 
 // Re-export public items
@@ -10,22 +10,41 @@ pub struct Loaded {
     pub admin: AdminSpec,
     pub defaults: DefaultsSpec,
     pub access_log: AccessLogSpec,
+    pub tracing: Option<TracingSpec>,
+    pub stats: StatsSpec,
+    pub overload: OverloadSpec,
     pub validate: ValidateSpec,
     pub listeners: ListenersSpec,
     pub domains: Vec<DomainSpec>,
     pub upstreams: Vec<UpstreamSpec>,
     pub policies: PoliciesSpec,
+    pub certs: Vec<CertSetSpec>,
 }
 
 pub fn load_all(config_dir: &Path) -> Result<Loaded> {
     let admin: AdminSpec = read_yaml(config_dir.join("common/admin.yaml"))?;
     let defaults: DefaultsSpec = read_yaml(config_dir.join("common/defaults.yaml"))?;
     let access_log: AccessLogSpec = read_yaml(config_dir.join("common/access_log.yaml"))?;
+    let tracing: Option<TracingSpec> = read_yaml_optional(config_dir.join("common/tracing.yaml"))?;
+    let stats: StatsSpec = read_yaml_optional(config_dir.join("common/stats.yaml"))?;
+    let overload: OverloadSpec = read_yaml_optional(config_dir.join("common/overload.yaml"))?;
     let runtime: RuntimeSpec = read_yaml(config_dir.join("common/runtime.yaml"))?;
-    let policies: PoliciesSpec = read_yaml(config_dir.join("policies/ratelimits.yaml"))?;
+    let mut policies: PoliciesSpec = read_yaml(config_dir.join("policies/ratelimits.yaml"))?;
+    let cors: CorsPoliciesFile = read_yaml_optional(config_dir.join("policies/cors.yaml"))?;
+    policies.cors.extend(cors.cors);
+    let jwt: JwtAuthFile = read_yaml_optional(config_dir.join("policies/jwt_auth.yaml"))?;
+    policies.jwt_providers.extend(jwt.providers);
+    let ext_authz: ExtAuthzFile = read_yaml_optional(config_dir.join("policies/ext_authz.yaml"))?;
+    policies.ext_authz.extend(ext_authz.ext_authz);
+    let access: AccessPolicyFile = read_yaml_optional(config_dir.join("policies/access.yaml"))?;
+    policies.access.extend(access.access);
+    let compression: CompressionPolicyFile =
+        read_yaml_optional(config_dir.join("policies/compression.yaml"))?;
+    policies.compression.extend(compression.compression);
     let listeners: ListenersSpec = read_yaml_optional(config_dir.join("common/listeners.yaml"))?;
 
     let domains = read_dir_yaml::<DomainSpec>(&config_dir.join("domains"))?;
+    let certs = read_dir_yaml::<CertSetSpec>(&config_dir.join("certs"))?;
 
     // Load upstreams from upstreams/ directory (strict - will error on parse failures)
     let mut upstreams = read_dir_yaml::<UpstreamSpec>(&config_dir.join("upstreams"))?;
@@ -37,11 +56,15 @@ pub fn load_all(config_dir: &Path) -> Result<Loaded> {
         admin,
         defaults,
         access_log,
+        tracing,
+        stats,
+        overload,
         validate: runtime.validate, // Extract validate from runtime
         listeners,
         domains,
         upstreams,
         policies,
+        certs,
     })
 }
 
@@ -261,12 +284,113 @@ port: 9000
         assert_eq!(loaded.admin.address, "0.0.0.0");
         assert_eq!(loaded.admin.port, 9901);
         assert_eq!(loaded.defaults.route_timeout, "60s");
-        assert_eq!(loaded.access_log.r#type, "stdout");
+        assert_eq!(loaded.access_log.r#type, AccessLogType::Stdout);
         assert_eq!(loaded.access_log.path, "/dev/stdout");
         assert_eq!(loaded.domains.len(), 0); // No domain files
         assert_eq!(loaded.upstreams.len(), 0); // No upstream files
     }
 
+    #[test]
+    fn test_load_all_with_cert_sets() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_dir = temp_dir.path().join("config");
+        fs::create_dir_all(config_dir.join("common")).unwrap();
+        fs::create_dir_all(config_dir.join("policies")).unwrap();
+        fs::create_dir_all(config_dir.join("certs")).unwrap();
+
+        fs::write(config_dir.join("common/admin.yaml"), "{}").unwrap();
+        fs::write(config_dir.join("common/defaults.yaml"), "{}").unwrap();
+        fs::write(config_dir.join("common/access_log.yaml"), "{}").unwrap();
+        fs::write(
+            config_dir.join("common/runtime.yaml"),
+            "validate: {type: \"native\"}",
+        )
+        .unwrap();
+        fs::write(config_dir.join("policies/ratelimits.yaml"), "").unwrap();
+        fs::write(
+            config_dir.join("certs/wildcard.yaml"),
+            r#"
+name: "wildcard_example_com"
+certificates:
+  - cert_chain: "/etc/envoy/certs/wildcard/rsa.pem"
+    private_key: "/etc/envoy/certs/wildcard/rsa.key"
+  - cert_chain: "/etc/envoy/certs/wildcard/ecdsa.pem"
+    private_key: "/etc/envoy/certs/wildcard/ecdsa.key"
+"#,
+        )
+        .unwrap();
+
+        let loaded = load_all(&config_dir).unwrap();
+        assert_eq!(loaded.certs.len(), 1);
+        assert_eq!(loaded.certs[0].name, "wildcard_example_com");
+        assert_eq!(loaded.certs[0].certificates.len(), 2);
+    }
+
+    #[test]
+    fn test_load_all_with_cors_policies() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_dir = temp_dir.path().join("config");
+        fs::create_dir_all(config_dir.join("common")).unwrap();
+        fs::create_dir_all(config_dir.join("policies")).unwrap();
+
+        fs::write(config_dir.join("common/admin.yaml"), "{}").unwrap();
+        fs::write(config_dir.join("common/defaults.yaml"), "{}").unwrap();
+        fs::write(config_dir.join("common/access_log.yaml"), "{}").unwrap();
+        fs::write(
+            config_dir.join("common/runtime.yaml"),
+            "validate: {type: \"native\"}",
+        )
+        .unwrap();
+        fs::write(config_dir.join("policies/ratelimits.yaml"), "").unwrap();
+        fs::write(
+            config_dir.join("policies/cors.yaml"),
+            r#"
+cors:
+  spa:
+    allow_origins:
+      - exact: "https://app.example.com"
+"#,
+        )
+        .unwrap();
+
+        let loaded = load_all(&config_dir).unwrap();
+        assert_eq!(loaded.policies.cors.len(), 1);
+        assert!(loaded.policies.cors.contains_key("spa"));
+    }
+
+    #[test]
+    fn test_load_all_with_tracing() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_dir = temp_dir.path().join("config");
+        fs::create_dir_all(config_dir.join("common")).unwrap();
+        fs::create_dir_all(config_dir.join("policies")).unwrap();
+
+        fs::write(config_dir.join("common/admin.yaml"), "{}").unwrap();
+        fs::write(config_dir.join("common/defaults.yaml"), "{}").unwrap();
+        fs::write(config_dir.join("common/access_log.yaml"), "{}").unwrap();
+        fs::write(
+            config_dir.join("common/runtime.yaml"),
+            "validate: {type: \"native\"}",
+        )
+        .unwrap();
+        fs::write(config_dir.join("policies/ratelimits.yaml"), "").unwrap();
+
+        let loaded = load_all(&config_dir).unwrap();
+        assert!(loaded.tracing.is_none());
+
+        fs::write(
+            config_dir.join("common/tracing.yaml"),
+            "provider: open_telemetry\nupstream: otel_collector\n",
+        )
+        .unwrap();
+        let loaded = load_all(&config_dir).unwrap();
+        let tracing = loaded.tracing.unwrap();
+        assert_eq!(tracing.provider, TracingProvider::OpenTelemetry);
+        assert_eq!(tracing.service_name, "envoy");
+        // tracing.yaml is not mistaken for an upstream
+        assert!(loaded.upstreams.is_empty());
+    }
+
     #[test]
     fn test_load_all_missing_common_dir() {
         let temp_dir = TempDir::new().unwrap();
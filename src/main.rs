@@ -5,6 +5,8 @@ mod generate;
 mod init;
 mod load;
 mod model;
+#[cfg(test)]
+mod test_fixtures;
 mod validate;
 
 use anyhow::Result;
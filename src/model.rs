@@ -7,6 +7,18 @@ pub struct AdminSpec {
     pub address: String,
     #[serde(default = "default_admin_port")]
     pub port: u16,
+    /// Unix domain socket to serve admin on instead of address/port
+    #[serde(default)]
+    pub socket_path: Option<String>,
+    /// Where /cpuprofiler writes its output
+    #[serde(default)]
+    pub profile_path: Option<String>,
+    /// Replaces the global access log for admin requests
+    #[serde(default)]
+    pub access_log: Option<AccessLogSpec>,
+    /// Public listener exposing only /ready and /stats/prometheus
+    #[serde(default)]
+    pub readonly_listener: Option<AdminReadonlyListenerSpec>,
 }
 fn default_admin_address() -> String {
     "0.0.0.0".into()
@@ -15,6 +27,41 @@ fn default_admin_port() -> u16 {
     9901
 }
 
+impl Default for AdminSpec {
+    fn default() -> Self {
+        Self {
+            address: default_admin_address(),
+            port: default_admin_port(),
+            socket_path: None,
+            profile_path: None,
+            access_log: None,
+            readonly_listener: None,
+        }
+    }
+}
+
+impl AdminSpec {
+    /// Whether admin is only reachable from this host
+    pub fn is_local_only(&self) -> bool {
+        self.socket_path.is_some()
+            || self.address == "localhost"
+            || self
+                .address
+                .parse::<std::net::IpAddr>()
+                .is_ok_and(|ip| ip.is_loopback())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AdminReadonlyListenerSpec {
+    #[serde(default = "default_readonly_listener_address")]
+    pub address: String,
+    pub port: u16,
+}
+fn default_readonly_listener_address() -> String {
+    "0.0.0.0".into()
+}
+
 #[derive(Debug, Deserialize)]
 pub struct DefaultsSpec {
     #[serde(default = "default_route_timeout")]
@@ -27,6 +74,109 @@ pub struct DefaultsSpec {
     /// Upstream name used by :443 default passthrough chain (TCP proxy)
     #[serde(default = "default_tls_passthrough_upstream")]
     pub tls_passthrough_upstream: String,
+
+    /// TLS parameters applied to every terminating domain unless overridden
+    #[serde(default)]
+    pub tls: TlsParamsSpec,
+
+    /// Connection limits for the HTTP and HTTPS listeners and, unless they set
+    /// their own, internal listeners
+    #[serde(default)]
+    pub listener_limits: ListenerLimitsSpec,
+
+    /// Bind settings for the plain HTTP listener (port 80 unless set)
+    #[serde(default)]
+    pub http_listener: BuiltinListenerSpec,
+
+    /// Bind settings for the HTTPS SNI listener (port 443 unless set)
+    #[serde(default)]
+    pub https_listener: BuiltinListenerSpec,
+}
+
+impl Default for DefaultsSpec {
+    fn default() -> Self {
+        Self {
+            route_timeout: default_route_timeout(),
+            http_default_upstream: default_http_upstream(),
+            tls_passthrough_upstream: default_tls_passthrough_upstream(),
+            tls: TlsParamsSpec::default(),
+            listener_limits: ListenerLimitsSpec::default(),
+            http_listener: BuiltinListenerSpec::default(),
+            https_listener: BuiltinListenerSpec::default(),
+        }
+    }
+}
+
+impl DefaultsSpec {
+    pub fn http_port(&self) -> u16 {
+        self.http_listener.port.unwrap_or(80)
+    }
+
+    pub fn https_port(&self) -> u16 {
+        self.https_listener.port.unwrap_or(443)
+    }
+}
+
+/// Where one of the built-in listeners binds, or whether it exists at all.
+#[derive(Debug, Deserialize)]
+pub struct BuiltinListenerSpec {
+    /// Set to false to leave the listener out of the generated config
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    #[serde(default = "default_listener_address")]
+    pub address: String,
+    /// Defaults to 80 for http_listener and 443 for https_listener
+    pub port: Option<u16>,
+    /// Accept IPv4 connections as well when bound to `::`
+    #[serde(default)]
+    pub ipv4_compat: bool,
+    /// Extra addresses the same listener binds to (e.g. `::` next to 0.0.0.0)
+    #[serde(default)]
+    pub additional_addresses: Vec<ListenerAddressSpec>,
+}
+
+impl Default for BuiltinListenerSpec {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            address: default_listener_address(),
+            port: None,
+            ipv4_compat: false,
+            additional_addresses: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ListenerAddressSpec {
+    pub address: String,
+    /// Defaults to the listener's own port
+    pub port: Option<u16>,
+}
+fn default_true() -> bool {
+    true
+}
+fn default_listener_address() -> String {
+    "0.0.0.0".into()
+}
+
+/// Connection-level protection for a listener. Both limits apply to the
+/// whole listener, across all of its filter chains.
+#[derive(Debug, Deserialize, Default, Clone)]
+pub struct ListenerLimitsSpec {
+    /// New connections allowed per fill interval (envoy.filters.network.local_ratelimit)
+    pub connection_rate_limit: Option<ConnectionRateLimitSpec>,
+    /// Maximum concurrent connections (envoy.filters.network.connection_limit)
+    pub max_connections: Option<u64>,
+    /// Read/write buffer limit per connection
+    pub per_connection_buffer_limit_bytes: Option<u32>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct ConnectionRateLimitSpec {
+    pub max_tokens: u32,
+    pub tokens_per_fill: u32,
+    pub fill_interval: String,
 }
 fn default_route_timeout() -> String {
     "60s".into()
@@ -38,22 +188,295 @@ fn default_tls_passthrough_upstream() -> String {
     "cilium_tls".into()
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Clone)]
 pub struct AccessLogSpec {
-    /// Log output type: "stdout" or "file" (reserved for future use)
-    #[serde(default = "default_log_type")]
-    #[allow(dead_code)]
-    pub r#type: String,
+    /// Log sink: stdout, stderr, file, open_telemetry or grpc
+    #[serde(default)]
+    pub r#type: AccessLogType,
+    /// Output path, used by the file sink
     #[serde(default = "default_log_path")]
     pub path: String,
-}
-fn default_log_type() -> String {
-    "stdout".into()
+    /// Text format string; Envoy's default format is used when unset
+    pub format: Option<String>,
+    /// Structured JSON format: output key -> command operator
+    pub json_format: Option<IndexMap<String, String>>,
+    pub filter: Option<AccessLogFilterSpec>,
+    /// Collector cluster for the open_telemetry and grpc sinks (needs http2)
+    pub upstream: Option<String>,
+    /// Log name reported to the collector
+    pub log_name: Option<String>,
+    /// Additional sinks written alongside this one
+    #[serde(default)]
+    pub sinks: Vec<AccessLogSpec>,
 }
 fn default_log_path() -> String {
     "/dev/stdout".into()
 }
 
+impl Default for AccessLogSpec {
+    fn default() -> Self {
+        Self {
+            r#type: AccessLogType::default(),
+            path: default_log_path(),
+            format: None,
+            json_format: None,
+            filter: None,
+            upstream: None,
+            log_name: None,
+            sinks: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Default, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum AccessLogType {
+    #[default]
+    Stdout,
+    Stderr,
+    File,
+    /// OpenTelemetry logs service (OTLP/gRPC)
+    OpenTelemetry,
+    /// Envoy's gRPC access log service (ALS)
+    Grpc,
+}
+
+impl AccessLogType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AccessLogType::Stdout => "stdout",
+            AccessLogType::Stderr => "stderr",
+            AccessLogType::File => "file",
+            AccessLogType::OpenTelemetry => "open_telemetry",
+            AccessLogType::Grpc => "grpc",
+        }
+    }
+
+    /// Whether the sink ships entries to a collector cluster.
+    pub fn is_remote(&self) -> bool {
+        matches!(self, AccessLogType::OpenTelemetry | AccessLogType::Grpc)
+    }
+}
+
+/// Conditions a request must meet to be logged. All configured conditions
+/// must hold.
+#[derive(Debug, Deserialize, Default, Clone)]
+pub struct AccessLogFilterSpec {
+    /// Log only responses whose status falls in one of these ranges
+    #[serde(default)]
+    pub status_codes: Vec<StatusCodeRangeSpec>,
+    /// Percentage of requests to log (0-100)
+    pub sample_percent: Option<u32>,
+    /// Skip requests marked as health checks
+    #[serde(default)]
+    pub not_health_check: bool,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct StatusCodeRangeSpec {
+    pub min: Option<u16>,
+    pub max: Option<u16>,
+}
+
+/// Loaded from common/tracing.yaml (optional). Every HTTP connection
+/// manager reports spans to the collector when present.
+#[derive(Debug, Deserialize)]
+pub struct TracingSpec {
+    pub provider: TracingProvider,
+    /// Collector cluster; open_telemetry needs http2
+    pub upstream: String,
+    #[serde(default = "default_tracing_service_name")]
+    pub service_name: String,
+    /// Zipkin only: path spans are POSTed to (default /api/v2/spans)
+    pub collector_endpoint: Option<String>,
+    #[serde(default)]
+    pub sampling: TracingSamplingSpec,
+    #[serde(default)]
+    pub custom_tags: Vec<CustomTagSpec>,
+}
+fn default_tracing_service_name() -> String {
+    "envoy".into()
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum TracingProvider {
+    /// OTLP over gRPC
+    OpenTelemetry,
+    /// Zipkin v2 JSON over HTTP
+    Zipkin,
+}
+
+impl TracingProvider {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TracingProvider::OpenTelemetry => "open_telemetry",
+            TracingProvider::Zipkin => "zipkin",
+        }
+    }
+}
+
+/// Sampling percentages (0-100). Envoy samples everything when unset.
+#[derive(Debug, Deserialize, Default, Clone)]
+pub struct TracingSamplingSpec {
+    /// Requests traced when no decision came with the request
+    pub random_sampling: Option<f64>,
+    /// Requests traced when the client forces it with x-client-trace-id
+    pub client_sampling: Option<f64>,
+    /// Cap applied after the other two
+    pub overall_sampling: Option<f64>,
+}
+
+impl TracingSamplingSpec {
+    /// Values set here, falling back to `base` field by field
+    pub fn or(&self, base: &TracingSamplingSpec) -> TracingSamplingSpec {
+        TracingSamplingSpec {
+            random_sampling: self.random_sampling.or(base.random_sampling),
+            client_sampling: self.client_sampling.or(base.client_sampling),
+            overall_sampling: self.overall_sampling.or(base.overall_sampling),
+        }
+    }
+}
+
+/// Span tag. Exactly one of `literal`, `request_header` or `environment`
+/// supplies the value.
+#[derive(Debug, Deserialize, Clone)]
+pub struct CustomTagSpec {
+    pub tag: String,
+    pub literal: Option<String>,
+    pub request_header: Option<String>,
+    pub environment: Option<String>,
+}
+
+impl CustomTagSpec {
+    pub fn is_valid(&self) -> bool {
+        [
+            self.literal.is_some(),
+            self.request_header.is_some(),
+            self.environment.is_some(),
+        ]
+        .iter()
+        .filter(|set| **set)
+        .count()
+            == 1
+    }
+}
+
+/// Per-domain tracing settings
+#[derive(Debug, Deserialize, Default)]
+pub struct DomainTracingSpec {
+    /// Overrides the matching common/tracing.yaml percentages
+    #[serde(default)]
+    pub sampling: TracingSamplingSpec,
+    /// Added after the common custom_tags
+    #[serde(default)]
+    pub custom_tags: Vec<CustomTagSpec>,
+}
+
+/// Loaded from common/stats.yaml (optional)
+#[derive(Debug, Deserialize, Default)]
+pub struct StatsSpec {
+    /// Collectors metrics are pushed to
+    #[serde(default)]
+    pub sinks: Vec<StatsSinkSpec>,
+    /// Extractors that move parts of stat names into tags (Prometheus labels)
+    #[serde(default)]
+    pub tags: Vec<StatsTagSpec>,
+    /// Keep Envoy's built-in extractors (cluster name, response code, ...)
+    pub use_all_default_tags: Option<bool>,
+    /// Only create stats whose name matches (exclusive with `exclude`)
+    #[serde(default)]
+    pub include: Vec<StringMatchSpec>,
+    /// Create every stat except those whose name matches
+    #[serde(default)]
+    pub exclude: Vec<StringMatchSpec>,
+}
+
+/// A statsd-protocol sink. `address`/`port` send UDP datagrams; statsd can
+/// use an `upstream` over TCP instead.
+#[derive(Debug, Deserialize)]
+pub struct StatsSinkSpec {
+    pub r#type: StatsSinkType,
+    /// IP address of the collector
+    pub address: Option<String>,
+    pub port: Option<u16>,
+    /// Collector cluster (statsd only)
+    pub upstream: Option<String>,
+    /// Prepended to every metric name
+    pub prefix: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum StatsSinkType {
+    Statsd,
+    /// statsd with DataDog tag extensions
+    Dogstatsd,
+}
+
+impl StatsSinkType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StatsSinkType::Statsd => "statsd",
+            StatsSinkType::Dogstatsd => "dogstatsd",
+        }
+    }
+}
+
+/// Tag named `tag_name`, taken from the first capture group of `regex` or
+/// set to `fixed_value` on every stat.
+#[derive(Debug, Deserialize)]
+pub struct StatsTagSpec {
+    pub tag_name: String,
+    pub regex: Option<String>,
+    pub fixed_value: Option<String>,
+}
+
+/// Loaded from common/overload.yaml (optional)
+#[derive(Debug, Deserialize, Default)]
+pub struct OverloadSpec {
+    /// How often resource monitors are sampled
+    pub refresh_interval: Option<String>,
+    /// Heap budget tracked by the fixed_heap monitor; actions trigger on it
+    pub max_heap_size_bytes: Option<u64>,
+    /// Hard cap on downstream connections enforced by the overload manager
+    pub max_active_downstream_connections: Option<u64>,
+    /// Same cap through the `overload.global_downstream_max_connections`
+    /// runtime key, for Envoy releases without the connections monitor
+    pub global_downstream_max_connections: Option<u64>,
+    #[serde(default)]
+    pub actions: Vec<OverloadActionSpec>,
+}
+
+/// Action taken once heap usage reaches `heap_threshold` (a fraction of
+/// max_heap_size_bytes between 0 and 1).
+#[derive(Debug, Deserialize)]
+pub struct OverloadActionSpec {
+    pub action: OverloadAction,
+    pub heap_threshold: f64,
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum OverloadAction {
+    /// Answer new requests with 503
+    StopAcceptingRequests,
+    /// Close connections after the current request
+    DisableHttpKeepalive,
+    /// Release free memory back to the system
+    ShrinkHeap,
+}
+
+impl OverloadAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OverloadAction::StopAcceptingRequests => "stop_accepting_requests",
+            OverloadAction::DisableHttpKeepalive => "disable_http_keepalive",
+            OverloadAction::ShrinkHeap => "shrink_heap",
+        }
+    }
+}
+
 #[derive(Debug, Deserialize)]
 pub struct RuntimeSpec {
     pub validate: ValidateSpec,
@@ -65,7 +488,7 @@ pub struct ListenersSpec {
     pub internal_http_listeners: Vec<InternalHttpListenerSpec>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Default)]
 pub struct InternalHttpListenerSpec {
     pub name: String,
     pub address: String,
@@ -76,6 +499,10 @@ pub struct InternalHttpListenerSpec {
     pub timeout: Option<String>,
     #[serde(default)]
     pub request_headers_to_add: Vec<HeaderValueOption>,
+    /// Replaces the global access log for this listener
+    pub access_log: Option<AccessLogSpec>,
+    /// Replaces defaults.listener_limits for this listener
+    pub limits: Option<ListenerLimitsSpec>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -144,29 +571,278 @@ pub struct UpstreamSpec {
     pub name: String,
     #[serde(default = "default_connect_timeout")]
     pub connect_timeout: String,
-    #[serde(default = "default_cluster_type")]
-    pub r#type: String,
-    #[serde(default = "default_lb_policy")]
-    pub lb_policy: String,
+    #[serde(default)]
+    pub r#type: ClusterType,
+    #[serde(default)]
+    pub lb_policy: LbPolicy,
+    /// Endpoints without locality information (priority 0)
+    #[serde(default)]
     pub endpoints: Vec<Endpoint>,
+    /// Endpoints grouped by locality and failover priority
+    #[serde(default)]
+    pub localities: Vec<LocalitySpec>,
     /// If true, add `http2_protocol_options: {}` (needed for h2c backends like Zitadel)
     #[serde(default)]
     pub http2: bool,
+    /// Protocol preset; `grpc` implies `http2` and adds keepalive pings
+    #[serde(default)]
+    pub preset: Option<UpstreamPreset>,
+    /// Passive health checking: eject hosts that keep failing
+    #[serde(default)]
+    pub outlier_detection: Option<OutlierDetectionSpec>,
+    /// Connection/request limits towards this upstream
+    #[serde(default)]
+    pub circuit_breakers: Option<CircuitBreakersSpec>,
+    /// Originate TLS to the backend (HTTPS upstreams)
+    #[serde(default)]
+    pub tls: Option<UpstreamTlsSpec>,
+    /// Options for `lb_policy: LEAST_REQUEST`
+    #[serde(default)]
+    pub least_request: Option<LeastRequestSpec>,
+    /// Options for `lb_policy: RING_HASH`
+    #[serde(default)]
+    pub ring_hash: Option<RingHashSpec>,
+    /// Options for `lb_policy: MAGLEV`
+    #[serde(default)]
+    pub maglev: Option<MaglevSpec>,
+    /// Address families used when resolving DNS endpoints
+    #[serde(default)]
+    pub dns_lookup_family: Option<DnsLookupFamily>,
+    /// Interval between DNS resolutions
+    #[serde(default)]
+    pub dns_refresh_rate: Option<String>,
+    /// Use the TTL of DNS records as the refresh rate
+    #[serde(default)]
+    pub respect_dns_ttl: Option<bool>,
+    /// File the endpoints are read from for `type: EDS`
+    #[serde(default)]
+    pub eds_config_path: Option<String>,
+}
+
+impl Default for UpstreamSpec {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            connect_timeout: default_connect_timeout(),
+            r#type: ClusterType::default(),
+            lb_policy: LbPolicy::default(),
+            endpoints: Vec::new(),
+            localities: Vec::new(),
+            http2: false,
+            preset: None,
+            outlier_detection: None,
+            circuit_breakers: None,
+            tls: None,
+            least_request: None,
+            ring_hash: None,
+            maglev: None,
+            dns_lookup_family: None,
+            dns_refresh_rate: None,
+            respect_dns_ttl: None,
+            eds_config_path: None,
+        }
+    }
+}
+
+impl UpstreamSpec {
+    /// Flat endpoints followed by the endpoints of every locality
+    pub fn all_endpoints(&self) -> impl Iterator<Item = &Endpoint> {
+        self.endpoints
+            .iter()
+            .chain(self.localities.iter().flat_map(|l| l.endpoints.iter()))
+    }
+
+    /// True when the cluster speaks HTTP/2 to its hosts
+    pub fn uses_http2(&self) -> bool {
+        self.http2 || self.preset == Some(UpstreamPreset::Grpc)
+    }
+}
+
+/// Bundles of cluster settings for common backend protocols
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum UpstreamPreset {
+    /// HTTP/2 with connection keepalive pings
+    Grpc,
 }
+
 fn default_connect_timeout() -> String {
     "5s".into()
 }
-fn default_cluster_type() -> String {
-    "STRICT_DNS".into()
+
+/// Envoy service discovery types
+#[derive(Debug, Deserialize, Default, Clone, Copy, PartialEq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ClusterType {
+    Static,
+    #[default]
+    StrictDns,
+    LogicalDns,
+    Eds,
 }
-fn default_lb_policy() -> String {
-    "ROUND_ROBIN".into()
+
+impl ClusterType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Static => "STATIC",
+            Self::StrictDns => "STRICT_DNS",
+            Self::LogicalDns => "LOGICAL_DNS",
+            Self::Eds => "EDS",
+        }
+    }
+
+    pub fn resolves_dns(&self) -> bool {
+        matches!(self, Self::StrictDns | Self::LogicalDns)
+    }
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum DnsLookupFamily {
+    Auto,
+    V4Only,
+    V6Only,
+    V4Preferred,
+    All,
+}
+
+impl DnsLookupFamily {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Auto => "AUTO",
+            Self::V4Only => "V4_ONLY",
+            Self::V6Only => "V6_ONLY",
+            Self::V4Preferred => "V4_PREFERRED",
+            Self::All => "ALL",
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Default, Clone, Copy, PartialEq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum LbPolicy {
+    #[default]
+    RoundRobin,
+    LeastRequest,
+    Random,
+    RingHash,
+    Maglev,
+}
+
+impl LbPolicy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::RoundRobin => "ROUND_ROBIN",
+            Self::LeastRequest => "LEAST_REQUEST",
+            Self::Random => "RANDOM",
+            Self::RingHash => "RING_HASH",
+            Self::Maglev => "MAGLEV",
+        }
+    }
+
+    /// Whether route `hash_policy` entries influence host selection
+    pub fn is_consistent_hash(&self) -> bool {
+        matches!(self, Self::RingHash | Self::Maglev)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LeastRequestSpec {
+    /// Number of random hosts compared when picking the least loaded one
+    pub choice_count: Option<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RingHashSpec {
+    pub minimum_ring_size: Option<u64>,
+    pub maximum_ring_size: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MaglevSpec {
+    /// Lookup table size; Envoy requires a prime number up to 5000011
+    pub table_size: Option<u64>,
 }
 
+/// Outlier detection (passive health checking) for an upstream.
+/// Unset fields fall back to Envoy defaults.
 #[derive(Debug, Deserialize)]
+pub struct OutlierDetectionSpec {
+    /// Consecutive 5xx responses before a host is ejected
+    pub consecutive_5xx: Option<u32>,
+    /// Consecutive gateway errors (502, 503, 504) before a host is ejected
+    pub consecutive_gateway_failure: Option<u32>,
+    /// Time between ejection analysis sweeps (e.g. "10s")
+    pub interval: Option<String>,
+    /// Base ejection time, multiplied by the number of times a host was ejected
+    pub base_ejection_time: Option<String>,
+    /// Maximum percentage of hosts that can be ejected at once (0-100)
+    pub max_ejection_percent: Option<u32>,
+}
+
+/// Circuit breaker thresholds, one set per routing priority
+#[derive(Debug, Deserialize)]
+pub struct CircuitBreakersSpec {
+    #[serde(default)]
+    pub default: Option<CircuitBreakerThresholds>,
+    #[serde(default)]
+    pub high: Option<CircuitBreakerThresholds>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CircuitBreakerThresholds {
+    pub max_connections: Option<u32>,
+    pub max_pending_requests: Option<u32>,
+    pub max_requests: Option<u32>,
+    pub max_retries: Option<u32>,
+}
+
+/// TLS settings for connections from Envoy to an upstream
+#[derive(Debug, Deserialize, Default)]
+pub struct UpstreamTlsSpec {
+    /// SNI sent to the backend; defaults to the first endpoint's host name
+    pub sni: Option<String>,
+    /// CA bundle used to verify the backend certificate
+    pub trusted_ca: Option<String>,
+    /// Connect without verifying the backend certificate (no trusted_ca)
+    #[serde(default)]
+    pub insecure_skip_verify: bool,
+    /// Client certificate chain presented to the backend (mTLS)
+    pub client_cert_chain: Option<String>,
+    /// Private key for client_cert_chain
+    pub client_private_key: Option<String>,
+    /// ALPN protocols offered to the backend (e.g. ["h2", "http/1.1"])
+    #[serde(default)]
+    pub alpn_protocols: Vec<String>,
+    /// Accepted DNS SANs of the backend certificate (requires trusted_ca)
+    #[serde(default)]
+    pub verify_san: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, Default)]
 pub struct Endpoint {
     pub address: String,
     pub port: u16,
+    /// Relative load balancing weight within its locality
+    #[serde(default)]
+    pub weight: Option<u32>,
+    /// Hostname used for auto host rewrite and health checks
+    #[serde(default)]
+    pub hostname: Option<String>,
+}
+
+/// A group of endpoints in one locality. Lower priorities are preferred;
+/// traffic fails over to the next priority when they become unhealthy.
+#[derive(Debug, Deserialize)]
+pub struct LocalitySpec {
+    pub region: Option<String>,
+    pub zone: Option<String>,
+    pub sub_zone: Option<String>,
+    #[serde(default)]
+    pub priority: u32,
+    /// Share of traffic for this locality within its priority
+    pub load_balancing_weight: Option<u32>,
+    pub endpoints: Vec<Endpoint>,
 }
 
 #[derive(Debug, Deserialize, Default)]
@@ -196,15 +872,224 @@ pub struct DomainSpec {
     /// AWS Request Signing configuration for upstream requests
     #[serde(default)]
     pub aws_signing: Option<AwsSigningSpec>,
+
+    /// Named CORS policy applied to every route of this domain
+    #[serde(default)]
+    pub cors: Option<String>,
+
+    /// JWT requirement applied to every route of this domain
+    #[serde(default)]
+    pub jwt: Option<JwtRequirementSpec>,
+
+    /// Named ext_authz policy checking every request to this domain
+    #[serde(default)]
+    pub ext_authz: Option<String>,
+
+    /// Named access (RBAC) policy applied to every route of this domain
+    #[serde(default)]
+    pub access: Option<String>,
+
+    /// Compression policies for responses, in order of preference
+    #[serde(default)]
+    pub compression: Vec<String>,
+
+    /// Replaces the global access log for this domain's connections
+    #[serde(default)]
+    pub access_log: Option<AccessLogSpec>,
+
+    /// Local rate limit bucket for routes without their own
+    /// per_filter_config.local_ratelimit
+    #[serde(default)]
+    pub local_ratelimit: Option<String>,
+
+    /// Sampling and tag overrides on top of common/tracing.yaml
+    #[serde(default)]
+    pub tracing: Option<DomainTracingSpec>,
 }
 fn default_mode() -> String {
     "terminate_https_443".into()
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Default)]
 pub struct TlsSpec {
+    /// Inline certificate pair (shorthand for a single entry in `certificates`)
+    #[serde(default)]
+    pub cert_chain: Option<String>,
+    #[serde(default)]
+    pub private_key: Option<String>,
+    /// OCSP response stapled for the inline certificate pair
+    #[serde(default)]
+    pub ocsp_staple: Option<String>,
+    /// Additional certificate pairs, e.g. RSA and ECDSA variants
+    #[serde(default)]
+    pub certificates: Vec<CertificateSpec>,
+    /// Name of a shared certificate set defined in certs/
+    #[serde(default)]
+    pub cert_set: Option<String>,
+
+    /// CA bundle used to verify client certificates (enables mTLS)
+    #[serde(default)]
+    pub client_ca: Option<String>,
+    /// Reject connections that do not present a client certificate (requires client_ca)
+    #[serde(default)]
+    pub require_client_certificate: bool,
+    /// Accepted DNS SANs of client certificates
+    #[serde(default)]
+    pub verify_san: Vec<String>,
+    /// Accepted base64 SHA-256 hashes of client certificate SubjectPublicKeyInfo
+    #[serde(default)]
+    pub verify_spki: Vec<String>,
+    /// Accepted hex SHA-256 hashes of client certificates
+    #[serde(default)]
+    pub verify_hash: Vec<String>,
+    /// Certificate revocation list checked against client certificates
+    #[serde(default)]
+    pub crl: Option<String>,
+
+    /// Protocol versions, ciphers, curves and ALPN (overrides defaults.tls)
+    #[serde(flatten)]
+    pub params: TlsParamsSpec,
+}
+
+impl TlsSpec {
+    /// All certificates served for the domain: inline pair, list, then the shared set
+    pub fn resolved_certificates(&self, cert_sets: &[CertSetSpec]) -> Vec<CertificateSpec> {
+        let mut out = Vec::new();
+        if let (Some(cert_chain), Some(private_key)) = (&self.cert_chain, &self.private_key) {
+            out.push(CertificateSpec {
+                cert_chain: cert_chain.clone(),
+                private_key: private_key.clone(),
+                ocsp_staple: self.ocsp_staple.clone(),
+            });
+        }
+        out.extend(self.certificates.iter().cloned());
+        if let Some(name) = &self.cert_set {
+            if let Some(set) = cert_sets.iter().find(|c| &c.name == name) {
+                out.extend(set.certificates.iter().cloned());
+            }
+        }
+        out
+    }
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct CertificateSpec {
     pub cert_chain: String,
     pub private_key: String,
+    #[serde(default)]
+    pub ocsp_staple: Option<String>,
+}
+
+/// Named certificate set from certs/, shared by many domains (e.g. a wildcard cert)
+#[derive(Debug, Deserialize)]
+pub struct CertSetSpec {
+    pub name: String,
+    pub certificates: Vec<CertificateSpec>,
+}
+
+/// TLS protocol parameters. Explicit fields win over the named profile.
+#[derive(Debug, Deserialize, Default, Clone, PartialEq)]
+pub struct TlsParamsSpec {
+    pub profile: Option<TlsProfile>,
+    pub tls_minimum_protocol_version: Option<TlsProtocolVersion>,
+    pub tls_maximum_protocol_version: Option<TlsProtocolVersion>,
+    pub cipher_suites: Option<Vec<String>>,
+    pub ecdh_curves: Option<Vec<String>>,
+    /// ALPN protocols offered to clients (e.g. ["h2", "http/1.1"])
+    pub alpn_protocols: Option<Vec<String>>,
+}
+
+impl TlsParamsSpec {
+    /// Expand the profile and fill unset fields from `fallback` (also expanded)
+    pub fn resolve(&self, fallback: &TlsParamsSpec) -> TlsParamsSpec {
+        let this = self.with_profile();
+        let fallback = fallback.with_profile();
+        TlsParamsSpec {
+            profile: this.profile.or(fallback.profile),
+            tls_minimum_protocol_version: this
+                .tls_minimum_protocol_version
+                .or(fallback.tls_minimum_protocol_version),
+            tls_maximum_protocol_version: this
+                .tls_maximum_protocol_version
+                .or(fallback.tls_maximum_protocol_version),
+            cipher_suites: this.cipher_suites.or(fallback.cipher_suites),
+            ecdh_curves: this.ecdh_curves.or(fallback.ecdh_curves),
+            alpn_protocols: this.alpn_protocols.or(fallback.alpn_protocols),
+        }
+    }
+
+    fn with_profile(&self) -> TlsParamsSpec {
+        let mut out = self.clone();
+        let Some(profile) = self.profile else {
+            return out;
+        };
+        let (min, max, ciphers, curves): (_, _, &[&str], &[&str]) = match profile {
+            // TLS 1.3 only; cipher suites are not configurable for 1.3
+            TlsProfile::Modern => (
+                TlsProtocolVersion::TlsV1_3,
+                TlsProtocolVersion::TlsV1_3,
+                &[],
+                &["X25519", "P-256"],
+            ),
+            // Mozilla "intermediate": TLS 1.2+ with AEAD ciphers only
+            TlsProfile::Intermediate => (
+                TlsProtocolVersion::TlsV1_2,
+                TlsProtocolVersion::TlsV1_3,
+                &[
+                    "ECDHE-ECDSA-AES128-GCM-SHA256",
+                    "ECDHE-RSA-AES128-GCM-SHA256",
+                    "ECDHE-ECDSA-AES256-GCM-SHA384",
+                    "ECDHE-RSA-AES256-GCM-SHA384",
+                    "ECDHE-ECDSA-CHACHA20-POLY1305",
+                    "ECDHE-RSA-CHACHA20-POLY1305",
+                ],
+                &["X25519", "P-256"],
+            ),
+        };
+        out.tls_minimum_protocol_version.get_or_insert(min);
+        out.tls_maximum_protocol_version.get_or_insert(max);
+        if out.cipher_suites.is_none() && !ciphers.is_empty() {
+            out.cipher_suites = Some(ciphers.iter().map(|c| c.to_string()).collect());
+        }
+        if out.ecdh_curves.is_none() {
+            out.ecdh_curves = Some(curves.iter().map(|c| c.to_string()).collect());
+        }
+        out
+    }
+}
+
+/// Named TLS presets based on the Mozilla server-side TLS guidelines
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum TlsProfile {
+    Modern,
+    Intermediate,
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum TlsProtocolVersion {
+    #[serde(rename = "TLS_AUTO")]
+    TlsAuto,
+    #[serde(rename = "TLSv1_0")]
+    TlsV1_0,
+    #[serde(rename = "TLSv1_1")]
+    TlsV1_1,
+    #[serde(rename = "TLSv1_2")]
+    TlsV1_2,
+    #[serde(rename = "TLSv1_3")]
+    TlsV1_3,
+}
+
+impl TlsProtocolVersion {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::TlsAuto => "TLS_AUTO",
+            Self::TlsV1_0 => "TLSv1_0",
+            Self::TlsV1_1 => "TLSv1_1",
+            Self::TlsV1_2 => "TLSv1_2",
+            Self::TlsV1_3 => "TLSv1_3",
+        }
+    }
 }
 
 /// AWS Request Signing configuration for upstream requests
@@ -258,6 +1143,87 @@ pub struct RouteSpec {
     /// Return a direct response instead of routing to upstream
     #[serde(default)]
     pub direct_response: Option<DirectResponseSpec>,
+
+    /// Request attributes hashed for RING_HASH / MAGLEV upstreams
+    #[serde(default)]
+    pub hash_policy: Vec<HashPolicySpec>,
+
+    /// Named CORS policy, overriding the domain's policy for this route
+    #[serde(default)]
+    pub cors: Option<String>,
+
+    /// JWT requirement, overriding the domain's requirement for this route
+    #[serde(default)]
+    pub jwt: Option<JwtRequirementSpec>,
+
+    /// Per-route ext_authz override
+    #[serde(default)]
+    pub ext_authz: Option<ExtAuthzRouteSpec>,
+
+    /// Named access (RBAC) policy, overriding the domain's policy for this route
+    #[serde(default)]
+    pub access: Option<String>,
+
+    /// Allow WebSocket upgrades on this route
+    #[serde(default)]
+    pub websocket: bool,
+
+    /// Match CONNECT requests and tunnel their payload to the upstream over
+    /// TCP. Replaces the prefix/path match.
+    #[serde(default)]
+    pub connect: bool,
+
+    /// Upper bound applied to the client's `grpc-timeout` header
+    /// ("0s" honours the header without a cap)
+    #[serde(default)]
+    pub max_grpc_timeout: Option<String>,
+
+    /// Subtracted from `grpc-timeout` so the upstream gives up before Envoy does
+    #[serde(default)]
+    pub grpc_timeout_header_offset: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct ExtAuthzRouteSpec {
+    /// Skip the authorization check for this route
+    #[serde(default)]
+    pub disabled: bool,
+}
+
+/// Which JWT provider a request must satisfy
+#[derive(Debug, Deserialize, Default)]
+pub struct JwtRequirementSpec {
+    /// Provider name from policies/jwt_auth.yaml
+    pub provider: Option<String>,
+    /// Let requests without a token through (invalid tokens are still rejected)
+    #[serde(default)]
+    pub allow_missing: bool,
+    /// Skip JWT verification (route level only)
+    #[serde(default)]
+    pub disabled: bool,
+    /// Path prefixes served without a token (domain level only)
+    #[serde(default)]
+    pub bypass_paths: Vec<String>,
+}
+
+/// One hash input. Exactly one of `header`, `cookie` or `source_ip` is set.
+#[derive(Debug, Deserialize)]
+pub struct HashPolicySpec {
+    pub header: Option<String>,
+    pub cookie: Option<HashCookieSpec>,
+    #[serde(default)]
+    pub source_ip: bool,
+    /// Stop evaluating further hash policies if this one produced a hash
+    #[serde(default)]
+    pub terminal: bool,
+}
+
+/// Cookie affinity. With a `ttl`, Envoy sets the cookie when it is missing.
+#[derive(Debug, Deserialize)]
+pub struct HashCookieSpec {
+    pub name: String,
+    pub ttl: Option<String>,
+    pub path: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -266,9 +1232,13 @@ pub struct DirectResponseSpec {
     pub body: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Default)]
 pub struct PerFilterConfigRef {
     pub local_ratelimit: Option<String>,
+    /// Global rate-limit policies from ratelimits.yaml, checked by the
+    /// rate limit service
+    #[serde(default)]
+    pub global_ratelimits: Vec<String>,
 }
 
 /// Match specification for routes
@@ -283,6 +1253,9 @@ pub struct MatchSpec {
     /// Header matchers for the route
     #[serde(default)]
     pub headers: Vec<HeaderMatcher>,
+    /// Only match gRPC requests (`content-type: application/grpc*`)
+    #[serde(default)]
+    pub grpc: bool,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -291,21 +1264,363 @@ pub struct HeaderMatcher {
     pub exact_match: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Default)]
 pub struct PoliciesSpec {
     #[serde(default)]
     pub local_ratelimits: IndexMap<String, TokenBucket>,
+    /// Descriptor policies sent to the external rate limit service
+    #[serde(default)]
+    pub global_ratelimits: IndexMap<String, GlobalRateLimitSpec>,
+    /// Rate limit service used by global_ratelimits
+    #[serde(default)]
+    pub ratelimit_service: Option<RateLimitServiceSpec>,
+    /// Loaded from policies/cors.yaml
+    #[serde(default)]
+    pub cors: IndexMap<String, CorsPolicySpec>,
+
+    /// Loaded from the `providers` map of policies/jwt_auth.yaml
+    #[serde(default)]
+    pub jwt_providers: IndexMap<String, JwtProviderSpec>,
+
+    /// Loaded from policies/ext_authz.yaml
+    #[serde(default)]
+    pub ext_authz: IndexMap<String, ExtAuthzSpec>,
+
+    /// Loaded from policies/access.yaml
+    #[serde(default)]
+    pub access: IndexMap<String, AccessPolicySpec>,
+
+    /// Loaded from policies/compression.yaml
+    #[serde(default)]
+    pub compression: IndexMap<String, CompressionPolicySpec>,
+}
+
+/// Contents of the optional policies/compression.yaml
+#[derive(Debug, Deserialize, Default)]
+pub struct CompressionPolicyFile {
+    #[serde(default)]
+    pub compression: IndexMap<String, CompressionPolicySpec>,
 }
 
 #[derive(Debug, Deserialize)]
+pub struct CompressionPolicySpec {
+    pub algorithm: CompressionAlgorithm,
+    /// Responses smaller than this many bytes are sent uncompressed
+    pub min_content_length: Option<u32>,
+    /// Content types to compress (Envoy's default list when empty)
+    #[serde(default)]
+    pub content_types: Vec<String>,
+    /// gzip: 1-9, brotli: 0-11, zstd: 1-22
+    pub level: Option<u32>,
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum CompressionAlgorithm {
+    Gzip,
+    Brotli,
+    Zstd,
+}
+
+impl CompressionAlgorithm {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Gzip => "gzip",
+            Self::Brotli => "brotli",
+            Self::Zstd => "zstd",
+        }
+    }
+
+    /// Accepted range for `level`
+    pub fn levels(&self) -> std::ops::RangeInclusive<u32> {
+        match self {
+            Self::Gzip => 1..=9,
+            Self::Brotli => 0..=11,
+            Self::Zstd => 1..=22,
+        }
+    }
+}
+
+/// Contents of the optional policies/access.yaml
+#[derive(Debug, Deserialize, Default)]
+pub struct AccessPolicyFile {
+    #[serde(default)]
+    pub access: IndexMap<String, AccessPolicySpec>,
+}
+
+/// IP and header based access control. A request matches when its source is
+/// in `source_cidrs` (if any), not in `except_cidrs`, and carries all `headers`.
+/// With `action: ALLOW` only matching requests pass; with `DENY` they are
+/// rejected, so the fields read the same either way.
+#[derive(Debug, Deserialize)]
+pub struct AccessPolicySpec {
+    #[serde(default)]
+    pub action: RbacAction,
+    #[serde(default)]
+    pub source_cidrs: Vec<String>,
+    #[serde(default)]
+    pub except_cidrs: Vec<String>,
+    #[serde(default)]
+    pub headers: Vec<HeaderMatcher>,
+    /// Also restrict the domain's TLS filter chain to `source_cidrs`
+    #[serde(default)]
+    pub enforce_l4: bool,
+}
+
+#[derive(Debug, Deserialize, Default, Clone, Copy, PartialEq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum RbacAction {
+    #[default]
+    Allow,
+    Deny,
+}
+
+impl RbacAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Allow => "ALLOW",
+            Self::Deny => "DENY",
+        }
+    }
+}
+
+/// Contents of the optional policies/ext_authz.yaml
+#[derive(Debug, Deserialize, Default)]
+pub struct ExtAuthzFile {
+    #[serde(default)]
+    pub ext_authz: IndexMap<String, ExtAuthzSpec>,
+}
+
+/// External authorization service consulted before requests are routed
+#[derive(Debug, Deserialize)]
+pub struct ExtAuthzSpec {
+    #[serde(default)]
+    pub mode: ExtAuthzMode,
+    /// Upstream running the authorization service
+    pub upstream: String,
+    #[serde(default = "default_ext_authz_timeout")]
+    pub timeout: String,
+    /// Let requests through when the authorization service is unreachable
+    #[serde(default)]
+    pub failure_mode_allow: bool,
+    /// Prefix added to the request path sent to an HTTP service
+    pub path_prefix: Option<String>,
+    /// Client request headers sent to the authorization service
+    #[serde(default)]
+    pub allowed_request_headers: Vec<String>,
+    /// Authorization response headers copied onto the upstream request
+    #[serde(default)]
+    pub allowed_upstream_headers: Vec<String>,
+}
+
+fn default_ext_authz_timeout() -> String {
+    "1s".into()
+}
+
+#[derive(Debug, Deserialize, Default, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ExtAuthzMode {
+    #[default]
+    Http,
+    Grpc,
+}
+
+/// Contents of the optional policies/cors.yaml
+#[derive(Debug, Deserialize, Default)]
+pub struct CorsPoliciesFile {
+    #[serde(default)]
+    pub cors: IndexMap<String, CorsPolicySpec>,
+}
+
+/// Contents of the optional policies/jwt_auth.yaml
+#[derive(Debug, Deserialize, Default)]
+pub struct JwtAuthFile {
+    #[serde(default)]
+    pub providers: IndexMap<String, JwtProviderSpec>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct JwtProviderSpec {
+    pub issuer: Option<String>,
+    #[serde(default)]
+    pub audiences: Vec<String>,
+    /// Path to a JWKS file on the Envoy host
+    pub local_jwks: Option<String>,
+    /// JWKS fetched over HTTP through an upstream
+    pub remote_jwks: Option<RemoteJwksSpec>,
+    /// Keep the Authorization header when forwarding upstream
+    #[serde(default)]
+    pub forward: bool,
+    /// Forward the verified payload (base64url JSON) in this header
+    pub forward_payload_header: Option<String>,
+    #[serde(default)]
+    pub claim_to_headers: Vec<ClaimToHeaderSpec>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RemoteJwksSpec {
+    pub uri: String,
+    /// Upstream (cluster) used to reach `uri`
+    pub upstream: String,
+    #[serde(default = "default_jwks_timeout")]
+    pub timeout: String,
+    pub cache_duration: Option<String>,
+}
+
+fn default_jwks_timeout() -> String {
+    "5s".into()
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ClaimToHeaderSpec {
+    pub claim: String,
+    pub header: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CorsPolicySpec {
+    pub allow_origins: Vec<StringMatchSpec>,
+    #[serde(default)]
+    pub allow_methods: Vec<String>,
+    #[serde(default)]
+    pub allow_headers: Vec<String>,
+    #[serde(default)]
+    pub expose_headers: Vec<String>,
+    /// Preflight cache lifetime in seconds
+    pub max_age: Option<u64>,
+    pub allow_credentials: Option<bool>,
+}
+
+/// String matcher written as `{ exact: .. }`, `{ prefix: .. }` or `{ regex: .. }`.
+/// Exactly one of the fields is set.
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct StringMatchSpec {
+    pub exact: Option<String>,
+    pub prefix: Option<String>,
+    pub regex: Option<String>,
+}
+
+impl StringMatchSpec {
+    pub fn is_valid(&self) -> bool {
+        [&self.exact, &self.prefix, &self.regex]
+            .iter()
+            .filter(|v| v.is_some())
+            .count()
+            == 1
+    }
+}
+
+#[derive(Debug, Deserialize, Default)]
 pub struct TokenBucket {
     pub max_tokens: u32,
     pub tokens_per_fill: u32,
     pub fill_interval: String,
     pub stat_prefix: Option<String>,
+    /// Percentage of requests the limit is evaluated for (default 100)
+    pub filter_enabled: Option<u32>,
+    /// Percentage of over-limit requests actually rejected (default 100).
+    /// 0 only records the `rate_limited` stat: report-only mode.
+    pub filter_enforced: Option<u32>,
+    /// Status code for rejected requests (Envoy default 429)
+    pub status: Option<u16>,
+    /// Headers added to rejected responses
+    #[serde(default)]
+    pub response_headers_to_add: Vec<HeaderValueOption>,
+    /// Separate buckets for specific header values or client addresses
+    #[serde(default)]
+    pub descriptors: Vec<LocalRateLimitDescriptorSpec>,
+}
+
+/// A bucket for requests matching one descriptor value. Set exactly one of
+/// `header` or `remote_address`; requests with any other value use the
+/// policy's main bucket.
+#[derive(Debug, Deserialize)]
+pub struct LocalRateLimitDescriptorSpec {
+    /// Request header whose value is matched
+    pub header: Option<String>,
+    /// Match the client address instead of a header
+    #[serde(default)]
+    pub remote_address: bool,
+    /// Exact header value or client IP
+    pub value: String,
+    pub max_tokens: u32,
+    pub tokens_per_fill: u32,
+    pub fill_interval: String,
+}
+
+impl LocalRateLimitDescriptorSpec {
+    /// Descriptor key produced by the route's rate limit action.
+    pub fn key(&self) -> &str {
+        self.header.as_deref().unwrap_or("remote_address")
+    }
+}
+
+/// Connection to an external rate limit service (envoyproxy/ratelimit or
+/// compatible) speaking the gRPC RateLimitService API.
+#[derive(Debug, Deserialize)]
+pub struct RateLimitServiceSpec {
+    /// Upstream cluster of the service; needs http2
+    pub upstream: String,
+    /// Rate limit domain the service's limits are configured under
+    pub domain: String,
+    #[serde(default = "default_ratelimit_timeout")]
+    pub timeout: String,
+    /// Reject requests when the service is unavailable (default: let them through)
+    #[serde(default)]
+    pub failure_mode_deny: bool,
+}
+fn default_ratelimit_timeout() -> String {
+    "20ms".into()
+}
+
+/// One `rate_limits` entry on a route. Each action adds an entry to the
+/// descriptor sent to the rate limit service.
+#[derive(Debug, Deserialize)]
+pub struct GlobalRateLimitSpec {
+    pub actions: Vec<RateLimitActionSpec>,
+}
+
+/// A descriptor action. Exactly one field must be set.
+#[derive(Debug, Deserialize, Default)]
+pub struct RateLimitActionSpec {
+    /// Client address (from x-forwarded-for when trusted hops are configured)
+    #[serde(default)]
+    pub remote_address: bool,
+    pub request_headers: Option<RequestHeadersActionSpec>,
+    pub generic_key: Option<GenericKeyActionSpec>,
+}
+
+impl RateLimitActionSpec {
+    pub fn is_valid(&self) -> bool {
+        [
+            self.remote_address,
+            self.request_headers.is_some(),
+            self.generic_key.is_some(),
+        ]
+        .iter()
+        .filter(|set| **set)
+        .count()
+            == 1
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RequestHeadersActionSpec {
+    pub header_name: String,
+    pub descriptor_key: String,
+    /// Skip this rate limit instead of sending no descriptor when the header is absent
+    #[serde(default)]
+    pub skip_if_absent: bool,
 }
 
 #[derive(Debug, Deserialize)]
+pub struct GenericKeyActionSpec {
+    pub descriptor_value: String,
+    /// Defaults to "generic_key" on the service side
+    pub descriptor_key: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Default)]
 pub struct HttpConnectionManagerSpec {
     pub stat_prefix: Option<String>,
     pub normalize_path: Option<bool>,
@@ -316,18 +1631,93 @@ pub struct HttpConnectionManagerSpec {
     pub local_ratelimit_stat_prefix: Option<String>,
     #[serde(default)]
     pub extra_http_filters: Vec<HttpFilterSpec>,
+    /// How the x-forwarded-client-cert header is handled
+    pub forward_client_cert_details: Option<ForwardClientCertDetails>,
+    /// Client certificate fields added to x-forwarded-client-cert
+    pub set_current_client_cert_details: Option<ClientCertDetailsSpec>,
+    /// Allow WebSocket upgrades on every route
+    #[serde(default)]
+    pub websocket: bool,
+}
+
+/// Envoy's x-forwarded-client-cert (XFCC) handling modes
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ForwardClientCertDetails {
+    Sanitize,
+    ForwardOnly,
+    AppendForward,
+    SanitizeSet,
+    AlwaysForwardOnly,
+}
+
+impl ForwardClientCertDetails {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Sanitize => "SANITIZE",
+            Self::ForwardOnly => "FORWARD_ONLY",
+            Self::AppendForward => "APPEND_FORWARD",
+            Self::SanitizeSet => "SANITIZE_SET",
+            Self::AlwaysForwardOnly => "ALWAYS_FORWARD_ONLY",
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct ClientCertDetailsSpec {
+    #[serde(default)]
+    pub subject: bool,
+    #[serde(default)]
+    pub cert: bool,
+    #[serde(default)]
+    pub chain: bool,
+    #[serde(default)]
+    pub dns: bool,
+    #[serde(default)]
+    pub uri: bool,
 }
 
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum HttpFilterSpec {
     GrpcWeb,
+    /// Per-service/method gRPC request and message counters
+    GrpcStats,
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use serde_yaml;
+
+    #[test]
+    fn test_default_matches_serde_defaults() {
+        let admin: AdminSpec = serde_yaml::from_str("{}").unwrap();
+        assert_eq!(AdminSpec::default().address, admin.address);
+        assert_eq!(AdminSpec::default().port, admin.port);
+
+        let defaults: DefaultsSpec = serde_yaml::from_str("{}").unwrap();
+        assert_eq!(
+            DefaultsSpec::default().route_timeout,
+            defaults.route_timeout
+        );
+        assert_eq!(
+            DefaultsSpec::default().http_default_upstream,
+            defaults.http_default_upstream
+        );
+        assert_eq!(
+            DefaultsSpec::default().tls_passthrough_upstream,
+            defaults.tls_passthrough_upstream
+        );
+
+        let log: AccessLogSpec = serde_yaml::from_str("{}").unwrap();
+        assert_eq!(AccessLogSpec::default().path, log.path);
+
+        let upstream: UpstreamSpec = serde_yaml::from_str("name: api").unwrap();
+        assert_eq!(
+            UpstreamSpec::default().connect_timeout,
+            upstream.connect_timeout
+        );
+    }
 
     #[test]
     fn test_deserialize_admin_spec() {
@@ -348,6 +1738,42 @@ port: 9000
         assert_eq!(admin.port, 9901); // default
     }
 
+    #[test]
+    fn test_deserialize_admin_spec_with_socket_and_readonly_listener() {
+        let yaml = r#"
+socket_path: /var/run/envoy/admin.sock
+profile_path: /var/lib/envoy/envoy.prof
+access_log:
+  type: file
+  path: /var/log/envoy/admin.log
+readonly_listener:
+  port: 9902
+"#;
+        let admin: AdminSpec = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(
+            admin.socket_path,
+            Some("/var/run/envoy/admin.sock".to_string())
+        );
+        assert_eq!(
+            admin.profile_path,
+            Some("/var/lib/envoy/envoy.prof".to_string())
+        );
+        assert_eq!(
+            admin.access_log.as_ref().unwrap().r#type,
+            AccessLogType::File
+        );
+        let readonly = admin.readonly_listener.as_ref().unwrap();
+        assert_eq!(readonly.address, "0.0.0.0");
+        assert_eq!(readonly.port, 9902);
+        // The socket is local even though address keeps its default
+        assert!(admin.is_local_only());
+
+        let exposed: AdminSpec = serde_yaml::from_str("{}").unwrap();
+        assert!(!exposed.is_local_only());
+        let local: AdminSpec = serde_yaml::from_str("address: \"::1\"").unwrap();
+        assert!(local.is_local_only());
+    }
+
     #[test]
     fn test_deserialize_defaults_spec() {
         let yaml = r#"
@@ -370,6 +1796,30 @@ tls_passthrough_upstream: "my_tls"
         assert_eq!(defaults.tls_passthrough_upstream, "cilium_tls"); // default
     }
 
+    #[test]
+    fn test_deserialize_defaults_spec_with_listener_limits() {
+        let yaml = r#"
+listener_limits:
+  connection_rate_limit:
+    max_tokens: 500
+    tokens_per_fill: 100
+    fill_interval: 1s
+  max_connections: 10000
+  per_connection_buffer_limit_bytes: 32768
+"#;
+        let defaults: DefaultsSpec = serde_yaml::from_str(yaml).unwrap();
+        let limits = &defaults.listener_limits;
+        assert_eq!(
+            limits.connection_rate_limit.as_ref().unwrap().max_tokens,
+            500
+        );
+        assert_eq!(limits.max_connections, Some(10000));
+        assert_eq!(limits.per_connection_buffer_limit_bytes, Some(32768));
+
+        let defaults: DefaultsSpec = serde_yaml::from_str("{}").unwrap();
+        assert!(defaults.listener_limits.max_connections.is_none());
+    }
+
     #[test]
     fn test_deserialize_upstream_spec() {
         let yaml = r#"
@@ -387,8 +1837,8 @@ endpoints:
         let upstream: UpstreamSpec = serde_yaml::from_str(yaml).unwrap();
         assert_eq!(upstream.name, "my_upstream");
         assert_eq!(upstream.connect_timeout, "10s");
-        assert_eq!(upstream.r#type, "STATIC");
-        assert_eq!(upstream.lb_policy, "ROUND_ROBIN");
+        assert_eq!(upstream.r#type, ClusterType::Static);
+        assert_eq!(upstream.lb_policy, LbPolicy::RoundRobin);
         assert!(upstream.http2);
         assert_eq!(upstream.endpoints.len(), 2);
         assert_eq!(upstream.endpoints[0].address, "127.0.0.1");
@@ -407,11 +1857,131 @@ endpoints:
 "#;
         let upstream: UpstreamSpec = serde_yaml::from_str(yaml).unwrap();
         assert_eq!(upstream.connect_timeout, "5s"); // default
-        assert_eq!(upstream.r#type, "STRICT_DNS"); // default
-        assert_eq!(upstream.lb_policy, "ROUND_ROBIN"); // default
+        assert_eq!(upstream.r#type, ClusterType::StrictDns); // default
+        assert_eq!(upstream.lb_policy, LbPolicy::RoundRobin); // default
         assert!(!upstream.http2); // default
     }
 
+    #[test]
+    fn test_deserialize_upstream_spec_with_resilience_options() {
+        let yaml = r#"
+name: "my_upstream"
+endpoints:
+  - address: "127.0.0.1"
+    port: 8080
+outlier_detection:
+  consecutive_5xx: 5
+  consecutive_gateway_failure: 3
+  base_ejection_time: "30s"
+  max_ejection_percent: 50
+circuit_breakers:
+  default:
+    max_connections: 1024
+    max_pending_requests: 256
+  high:
+    max_requests: 2048
+    max_retries: 3
+"#;
+        let upstream: UpstreamSpec = serde_yaml::from_str(yaml).unwrap();
+        let od = upstream.outlier_detection.unwrap();
+        assert_eq!(od.consecutive_5xx, Some(5));
+        assert_eq!(od.consecutive_gateway_failure, Some(3));
+        assert_eq!(od.base_ejection_time, Some("30s".to_string()));
+        assert_eq!(od.max_ejection_percent, Some(50));
+        assert!(od.interval.is_none());
+
+        let cb = upstream.circuit_breakers.unwrap();
+        let default = cb.default.unwrap();
+        assert_eq!(default.max_connections, Some(1024));
+        assert_eq!(default.max_pending_requests, Some(256));
+        let high = cb.high.unwrap();
+        assert_eq!(high.max_requests, Some(2048));
+        assert_eq!(high.max_retries, Some(3));
+    }
+
+    #[test]
+    fn test_deserialize_upstream_spec_with_tls() {
+        let yaml = r#"
+name: "secure_backend"
+endpoints:
+  - address: "backend.internal"
+    port: 443
+tls:
+  sni: "backend.internal"
+  trusted_ca: "/etc/ssl/certs/ca.pem"
+  client_cert_chain: "/etc/envoy/client.pem"
+  client_private_key: "/etc/envoy/client.key"
+  alpn_protocols: ["h2", "http/1.1"]
+  verify_san: ["backend.internal"]
+"#;
+        let upstream: UpstreamSpec = serde_yaml::from_str(yaml).unwrap();
+        let tls = upstream.tls.unwrap();
+        assert_eq!(tls.sni, Some("backend.internal".to_string()));
+        assert_eq!(tls.trusted_ca, Some("/etc/ssl/certs/ca.pem".to_string()));
+        assert_eq!(
+            tls.client_cert_chain,
+            Some("/etc/envoy/client.pem".to_string())
+        );
+        assert_eq!(
+            tls.client_private_key,
+            Some("/etc/envoy/client.key".to_string())
+        );
+        assert_eq!(tls.alpn_protocols, vec!["h2", "http/1.1"]);
+        assert_eq!(tls.verify_san, vec!["backend.internal"]);
+    }
+
+    #[test]
+    fn test_deserialize_upstream_spec_with_localities() {
+        let yaml = r#"
+name: "api"
+localities:
+  - region: "eu-west"
+    zone: "dc1"
+    priority: 0
+    endpoints:
+      - { address: "10.0.1.10", port: 8080, weight: 3 }
+      - { address: "10.0.1.11", port: 8080, hostname: "api-2.dc1" }
+  - region: "eu-central"
+    zone: "dc2"
+    priority: 1
+    endpoints:
+      - { address: "10.0.2.10", port: 8080 }
+"#;
+        let upstream: UpstreamSpec = serde_yaml::from_str(yaml).unwrap();
+        assert!(upstream.endpoints.is_empty());
+        assert_eq!(upstream.localities.len(), 2);
+        assert_eq!(upstream.localities[0].zone, Some("dc1".to_string()));
+        assert_eq!(upstream.localities[0].endpoints[0].weight, Some(3));
+        assert_eq!(
+            upstream.localities[0].endpoints[1].hostname,
+            Some("api-2.dc1".to_string())
+        );
+        assert_eq!(upstream.localities[1].priority, 1);
+        assert_eq!(upstream.all_endpoints().count(), 3);
+    }
+
+    #[test]
+    fn test_deserialize_upstream_spec_with_dns_options() {
+        let yaml = r#"
+name: "api"
+type: LOGICAL_DNS
+dns_lookup_family: V4_ONLY
+dns_refresh_rate: 30s
+respect_dns_ttl: true
+endpoints:
+  - { address: "api.internal", port: 8080 }
+"#;
+        let upstream: UpstreamSpec = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(upstream.r#type, ClusterType::LogicalDns);
+        assert_eq!(upstream.dns_lookup_family, Some(DnsLookupFamily::V4Only));
+        assert_eq!(upstream.dns_refresh_rate, Some("30s".to_string()));
+        assert_eq!(upstream.respect_dns_ttl, Some(true));
+
+        let bad: Result<UpstreamSpec, _> =
+            serde_yaml::from_str("{ name: x, type: STRICT_DSN, endpoints: [] }");
+        assert!(bad.is_err());
+    }
+
     #[test]
     fn test_deserialize_domain_spec() {
         let yaml = r#"
@@ -438,6 +2008,119 @@ routes:
         assert_eq!(domain.routes[0].timeout, Some("30s".to_string()));
     }
 
+    #[test]
+    fn test_deserialize_domain_spec_with_client_certificates() {
+        let yaml = r#"
+domain: "partner.example.com"
+tls:
+  cert_chain: "/path/to/cert"
+  private_key: "/path/to/key"
+  client_ca: "/path/to/partner-ca.pem"
+  require_client_certificate: true
+  verify_san: ["client.partner.com"]
+  crl: "/path/to/partner.crl"
+http_connection_manager:
+  forward_client_cert_details: SANITIZE_SET
+  set_current_client_cert_details:
+    subject: true
+    uri: true
+"#;
+        let domain: DomainSpec = serde_yaml::from_str(yaml).unwrap();
+        let tls = domain.tls.unwrap();
+        assert_eq!(tls.client_ca, Some("/path/to/partner-ca.pem".to_string()));
+        assert!(tls.require_client_certificate);
+        assert_eq!(tls.verify_san, vec!["client.partner.com"]);
+        assert!(tls.verify_spki.is_empty());
+        assert_eq!(tls.crl, Some("/path/to/partner.crl".to_string()));
+
+        let hcm = domain.http_connection_manager.unwrap();
+        assert_eq!(
+            hcm.forward_client_cert_details,
+            Some(ForwardClientCertDetails::SanitizeSet)
+        );
+        let details = hcm.set_current_client_cert_details.unwrap();
+        assert!(details.subject && details.uri);
+        assert!(!details.cert && !details.chain && !details.dns);
+    }
+
+    #[test]
+    fn test_deserialize_tls_params_and_profile_resolution() {
+        let yaml = r#"
+domain: "example.com"
+tls:
+  cert_chain: "/path/to/cert"
+  private_key: "/path/to/key"
+  profile: intermediate
+  cipher_suites: ["ECDHE-RSA-AES128-GCM-SHA256"]
+  alpn_protocols: ["h2", "http/1.1"]
+"#;
+        let domain: DomainSpec = serde_yaml::from_str(yaml).unwrap();
+        let params = domain.tls.unwrap().params;
+        assert_eq!(params.profile, Some(TlsProfile::Intermediate));
+
+        let defaults: DefaultsSpec = serde_yaml::from_str(
+            r#"
+tls:
+  tls_maximum_protocol_version: TLSv1_2
+  ecdh_curves: ["P-256"]
+"#,
+        )
+        .unwrap();
+
+        let resolved = params.resolve(&defaults.tls);
+        // Profile supplies the minimum version
+        assert_eq!(
+            resolved.tls_minimum_protocol_version,
+            Some(TlsProtocolVersion::TlsV1_2)
+        );
+        // Profile of the domain wins over the global explicit value
+        assert_eq!(
+            resolved.tls_maximum_protocol_version,
+            Some(TlsProtocolVersion::TlsV1_3)
+        );
+        // Explicit domain value wins over the profile
+        assert_eq!(
+            resolved.cipher_suites,
+            Some(vec!["ECDHE-RSA-AES128-GCM-SHA256".to_string()])
+        );
+        assert_eq!(
+            resolved.alpn_protocols,
+            Some(vec!["h2".to_string(), "http/1.1".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_tls_resolved_certificates() {
+        let tls: TlsSpec = serde_yaml::from_str(
+            r#"
+cert_chain: "/certs/rsa.pem"
+private_key: "/certs/rsa.key"
+ocsp_staple: "/certs/rsa.ocsp"
+certificates:
+  - cert_chain: "/certs/ecdsa.pem"
+    private_key: "/certs/ecdsa.key"
+cert_set: "wildcard"
+"#,
+        )
+        .unwrap();
+        let sets: Vec<CertSetSpec> = vec![serde_yaml::from_str(
+            r#"
+name: "wildcard"
+certificates:
+  - cert_chain: "/certs/wildcard.pem"
+    private_key: "/certs/wildcard.key"
+"#,
+        )
+        .unwrap()];
+
+        let certs = tls.resolved_certificates(&sets);
+        assert_eq!(certs.len(), 3);
+        assert_eq!(certs[0].cert_chain, "/certs/rsa.pem");
+        assert_eq!(certs[0].ocsp_staple, Some("/certs/rsa.ocsp".to_string()));
+        assert_eq!(certs[1].cert_chain, "/certs/ecdsa.pem");
+        assert_eq!(certs[2].cert_chain, "/certs/wildcard.pem");
+    }
+
     #[test]
     fn test_deserialize_domain_spec_with_defaults() {
         let yaml = r#"
@@ -450,6 +2133,187 @@ routes: []
         assert_eq!(domain.routes.len(), 0);
     }
 
+    #[test]
+    fn test_deserialize_websocket_and_connect_routes() {
+        let yaml = r#"
+domain: chat.example.com
+http_connection_manager:
+  websocket: true
+routes:
+  - match: { prefix: "/ws/" }
+    to_upstream: chat
+    websocket: true
+    timeout: 0s
+  - connect: true
+    to_upstream: tunnel
+"#;
+        let domain: DomainSpec = serde_yaml::from_str(yaml).unwrap();
+        assert!(domain.http_connection_manager.unwrap().websocket);
+        assert!(domain.routes[0].websocket);
+        assert!(!domain.routes[0].connect);
+        assert!(domain.routes[1].connect);
+        assert!(domain.routes[1].m.prefix.is_none());
+    }
+
+    #[test]
+    fn test_deserialize_grpc_route_and_upstream_preset() {
+        let yaml = r#"
+domain: api.example.com
+http_connection_manager:
+  extra_http_filters: [grpc_web, grpc_stats]
+routes:
+  - match: { prefix: "/orders.v1.OrderService/", grpc: true }
+    to_upstream: orders
+    max_grpc_timeout: 30s
+    grpc_timeout_header_offset: 0.05s
+"#;
+        let domain: DomainSpec = serde_yaml::from_str(yaml).unwrap();
+        let hcm = domain.http_connection_manager.unwrap();
+        assert!(matches!(
+            hcm.extra_http_filters[1],
+            HttpFilterSpec::GrpcStats
+        ));
+        let route = &domain.routes[0];
+        assert!(route.m.grpc);
+        assert_eq!(route.max_grpc_timeout, Some("30s".to_string()));
+        assert_eq!(route.grpc_timeout_header_offset, Some("0.05s".to_string()));
+
+        let upstream: UpstreamSpec =
+            serde_yaml::from_str("{ name: orders, preset: grpc, endpoints: [] }").unwrap();
+        assert_eq!(upstream.preset, Some(UpstreamPreset::Grpc));
+        assert!(!upstream.http2);
+        assert!(upstream.uses_http2());
+    }
+
+    #[test]
+    fn test_deserialize_tracing_with_domain_override() {
+        let yaml = r#"
+provider: zipkin
+upstream: zipkin
+service_name: edge-proxy
+sampling:
+  random_sampling: 5
+  overall_sampling: 50.5
+custom_tags:
+  - { tag: env, literal: production }
+  - { tag: pod, environment: HOSTNAME }
+"#;
+        let tracing: TracingSpec = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(tracing.provider, TracingProvider::Zipkin);
+        assert_eq!(tracing.service_name, "edge-proxy");
+        assert!(tracing.collector_endpoint.is_none());
+        assert_eq!(tracing.sampling.random_sampling, Some(5.0));
+        assert!(tracing.custom_tags.iter().all(CustomTagSpec::is_valid));
+
+        let domain: DomainSpec = serde_yaml::from_str(
+            r#"
+domain: api.example.com
+tracing:
+  sampling: { random_sampling: 100 }
+  custom_tags:
+    - { tag: tenant, request_header: x-tenant-id }
+"#,
+        )
+        .unwrap();
+        let dt = domain.tracing.unwrap();
+        let merged = dt.sampling.or(&tracing.sampling);
+        assert_eq!(merged.random_sampling, Some(100.0));
+        assert_eq!(merged.overall_sampling, Some(50.5));
+        assert_eq!(merged.client_sampling, None);
+        assert_eq!(
+            dt.custom_tags[0].request_header,
+            Some("x-tenant-id".to_string())
+        );
+    }
+
+    #[test]
+    fn test_deserialize_stats_spec() {
+        let yaml = r#"
+sinks:
+  - { type: dogstatsd, address: 127.0.0.1, port: 8125, prefix: envoy }
+  - { type: statsd, upstream: statsd_exporter }
+tags:
+  - tag_name: local_ratelimit
+    regex: "^((.+?)\\.)http_local_rate_limit\\."
+  - { tag_name: region, fixed_value: eu-west-1 }
+exclude:
+  - prefix: "cluster.default_"
+"#;
+        let stats: StatsSpec = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(stats.sinks[0].r#type, StatsSinkType::Dogstatsd);
+        assert_eq!(stats.sinks[0].port, Some(8125));
+        assert_eq!(stats.sinks[1].upstream, Some("statsd_exporter".to_string()));
+        assert_eq!(
+            stats.tags[0].regex,
+            Some("^((.+?)\\.)http_local_rate_limit\\.".to_string())
+        );
+        assert_eq!(stats.tags[1].fixed_value, Some("eu-west-1".to_string()));
+        assert!(stats.include.is_empty());
+        assert_eq!(
+            stats.exclude[0].prefix,
+            Some("cluster.default_".to_string())
+        );
+        assert!(stats.use_all_default_tags.is_none());
+    }
+
+    #[test]
+    fn test_deserialize_overload_spec() {
+        let yaml = r#"
+refresh_interval: 0.25s
+max_heap_size_bytes: 2147483648
+max_active_downstream_connections: 50000
+actions:
+  - { action: shrink_heap, heap_threshold: 0.9 }
+  - { action: stop_accepting_requests, heap_threshold: 0.95 }
+"#;
+        let overload: OverloadSpec = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(overload.refresh_interval, Some("0.25s".to_string()));
+        assert_eq!(overload.max_heap_size_bytes, Some(2_147_483_648));
+        assert_eq!(overload.max_active_downstream_connections, Some(50_000));
+        assert!(overload.global_downstream_max_connections.is_none());
+        assert_eq!(overload.actions[0].action, OverloadAction::ShrinkHeap);
+        assert_eq!(overload.actions[1].heap_threshold, 0.95);
+
+        let bad: Result<OverloadSpec, _> =
+            serde_yaml::from_str("actions: [{ action: drop_everything, heap_threshold: 0.5 }]");
+        assert!(bad.is_err());
+    }
+
+    #[test]
+    fn test_deserialize_builtin_listener_bind_settings() {
+        let yaml = r#"
+http_default_upstream: web
+tls_passthrough_upstream: web
+http_listener:
+  port: 8080
+https_listener:
+  address: "::"
+  port: 8443
+  ipv4_compat: true
+  additional_addresses:
+    - { address: "10.0.0.5" }
+"#;
+        let defaults: DefaultsSpec = serde_yaml::from_str(yaml).unwrap();
+        assert!(defaults.http_listener.enabled);
+        assert_eq!(defaults.http_listener.address, "0.0.0.0");
+        assert_eq!(defaults.http_port(), 8080);
+        assert_eq!(defaults.https_listener.address, "::");
+        assert_eq!(defaults.https_port(), 8443);
+        assert!(defaults.https_listener.ipv4_compat);
+        assert_eq!(
+            defaults.https_listener.additional_addresses[0].address,
+            "10.0.0.5"
+        );
+        assert!(defaults.https_listener.additional_addresses[0]
+            .port
+            .is_none());
+
+        let off: DefaultsSpec = serde_yaml::from_str("http_listener: { enabled: false }").unwrap();
+        assert!(!off.http_listener.enabled);
+        assert_eq!(off.http_port(), 80);
+        assert_eq!(off.https_port(), 443);
+    }
+
     #[test]
     fn test_deserialize_route_spec_with_per_filter_config() {
         let yaml = r#"
@@ -469,6 +2333,42 @@ per_filter_config:
         );
     }
 
+    #[test]
+    fn test_deserialize_lb_policy_and_hash_policy() {
+        let yaml = r#"
+name: "sessions"
+lb_policy: RING_HASH
+ring_hash:
+  minimum_ring_size: 1024
+endpoints:
+  - { address: "app", port: 8080 }
+"#;
+        let upstream: UpstreamSpec = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(upstream.lb_policy, LbPolicy::RingHash);
+        assert!(upstream.lb_policy.is_consistent_hash());
+        assert_eq!(upstream.ring_hash.unwrap().minimum_ring_size, Some(1024));
+
+        let bad: Result<UpstreamSpec, _> =
+            serde_yaml::from_str("{ name: x, lb_policy: ROUND_ROBBIN, endpoints: [] }");
+        assert!(bad.is_err());
+
+        let yaml = r#"
+match: { prefix: "/ws" }
+to_upstream: "sessions"
+hash_policy:
+  - cookie: { name: "sticky", ttl: "3600s" }
+    terminal: true
+  - source_ip: true
+"#;
+        let route: RouteSpec = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(route.hash_policy.len(), 2);
+        let cookie = route.hash_policy[0].cookie.as_ref().unwrap();
+        assert_eq!(cookie.name, "sticky");
+        assert_eq!(cookie.ttl, Some("3600s".to_string()));
+        assert!(route.hash_policy[0].terminal);
+        assert!(route.hash_policy[1].source_ip);
+    }
+
     #[test]
     fn test_deserialize_policies_spec() {
         let yaml = r#"
@@ -493,6 +2393,283 @@ local_ratelimits:
         assert_eq!(strict.fill_interval, "1s");
     }
 
+    #[test]
+    fn test_deserialize_token_bucket_with_descriptors() {
+        let yaml = r#"
+local_ratelimits:
+  api:
+    max_tokens: 100
+    tokens_per_fill: 100
+    fill_interval: 1s
+    filter_enforced: 0
+    status: 503
+    response_headers_to_add:
+      - header: { key: x-ratelimited, value: "true" }
+    descriptors:
+      - header: x-tenant
+        value: free
+        max_tokens: 10
+        tokens_per_fill: 10
+        fill_interval: 1s
+      - remote_address: true
+        value: 203.0.113.7
+        max_tokens: 1
+        tokens_per_fill: 1
+        fill_interval: 1s
+"#;
+        let policies: PoliciesSpec = serde_yaml::from_str(yaml).unwrap();
+        let api = &policies.local_ratelimits["api"];
+        assert_eq!(api.filter_enabled, None);
+        assert_eq!(api.filter_enforced, Some(0));
+        assert_eq!(api.status, Some(503));
+        assert_eq!(api.response_headers_to_add[0].header.key, "x-ratelimited");
+        assert_eq!(api.descriptors[0].key(), "x-tenant");
+        assert_eq!(api.descriptors[1].key(), "remote_address");
+        assert_eq!(api.descriptors[1].max_tokens, 1);
+    }
+
+    #[test]
+    fn test_deserialize_global_ratelimits() {
+        let yaml = r#"
+ratelimit_service:
+  upstream: ratelimit
+  domain: edge
+global_ratelimits:
+  per_client:
+    actions:
+      - remote_address: true
+  per_api_key:
+    actions:
+      - generic_key: { descriptor_value: api }
+      - request_headers: { header_name: x-api-key, descriptor_key: api_key }
+"#;
+        let policies: PoliciesSpec = serde_yaml::from_str(yaml).unwrap();
+        let service = policies.ratelimit_service.unwrap();
+        assert_eq!(service.domain, "edge");
+        assert_eq!(service.timeout, "20ms");
+        assert!(!service.failure_mode_deny);
+        assert!(policies.global_ratelimits["per_client"].actions[0].remote_address);
+        let per_key = &policies.global_ratelimits["per_api_key"].actions;
+        assert_eq!(
+            per_key[1].request_headers.as_ref().unwrap().header_name,
+            "x-api-key"
+        );
+        assert!(per_key.iter().all(RateLimitActionSpec::is_valid));
+        assert!(!RateLimitActionSpec::default().is_valid());
+    }
+
+    #[test]
+    fn test_deserialize_cors_policies() {
+        let yaml = r#"
+cors:
+  spa:
+    allow_origins:
+      - exact: "https://app.example.com"
+      - prefix: "https://preview-"
+      - regex: "https://.*\\.example\\.org"
+    allow_methods: ["GET", "POST"]
+    allow_headers: ["authorization", "content-type"]
+    max_age: 86400
+    allow_credentials: true
+"#;
+        let file: CorsPoliciesFile = serde_yaml::from_str(yaml).unwrap();
+        let spa = &file.cors["spa"];
+        assert_eq!(spa.allow_origins.len(), 3);
+        assert_eq!(
+            spa.allow_origins[0].exact,
+            Some("https://app.example.com".to_string())
+        );
+        assert_eq!(
+            spa.allow_origins[1].prefix,
+            Some("https://preview-".to_string())
+        );
+        assert!(spa.allow_origins.iter().all(StringMatchSpec::is_valid));
+        assert_eq!(spa.allow_methods, vec!["GET", "POST"]);
+        assert!(spa.expose_headers.is_empty());
+        assert_eq!(spa.max_age, Some(86400));
+        assert_eq!(spa.allow_credentials, Some(true));
+    }
+
+    #[test]
+    fn test_deserialize_jwt_auth_and_requirements() {
+        let yaml = r#"
+providers:
+  auth0:
+    issuer: "https://example.eu.auth0.com/"
+    audiences: ["api.example.com"]
+    remote_jwks:
+      uri: "https://example.eu.auth0.com/.well-known/jwks.json"
+      upstream: "auth0_jwks"
+      cache_duration: 600s
+    forward_payload_header: "x-jwt-payload"
+    claim_to_headers:
+      - { claim: "sub", header: "x-user-id" }
+  internal:
+    local_jwks: "/etc/envoy/jwks/internal.json"
+"#;
+        let file: JwtAuthFile = serde_yaml::from_str(yaml).unwrap();
+        let auth0 = &file.providers["auth0"];
+        let remote = auth0.remote_jwks.as_ref().unwrap();
+        assert_eq!(remote.upstream, "auth0_jwks");
+        assert_eq!(remote.timeout, "5s"); // default
+        assert_eq!(auth0.claim_to_headers[0].header, "x-user-id");
+        assert!(!auth0.forward);
+        assert_eq!(
+            file.providers["internal"].local_jwks,
+            Some("/etc/envoy/jwks/internal.json".to_string())
+        );
+
+        let yaml = r#"
+domain: "api.example.com"
+jwt: { provider: "auth0", bypass_paths: ["/health"] }
+routes:
+  - match: { prefix: "/public/" }
+    to_upstream: "api"
+    jwt: { disabled: true }
+"#;
+        let domain: DomainSpec = serde_yaml::from_str(yaml).unwrap();
+        let jwt = domain.jwt.unwrap();
+        assert_eq!(jwt.provider, Some("auth0".to_string()));
+        assert_eq!(jwt.bypass_paths, vec!["/health"]);
+        assert!(domain.routes[0].jwt.as_ref().unwrap().disabled);
+    }
+
+    #[test]
+    fn test_deserialize_ext_authz_policies() {
+        let yaml = r#"
+ext_authz:
+  oauth2_proxy:
+    upstream: "oauth2_proxy"
+    path_prefix: "/oauth2/auth"
+    allowed_request_headers: ["cookie", "authorization"]
+    allowed_upstream_headers: ["x-auth-request-user"]
+  opa:
+    mode: grpc
+    upstream: "opa"
+    timeout: 250ms
+    failure_mode_allow: true
+"#;
+        let file: ExtAuthzFile = serde_yaml::from_str(yaml).unwrap();
+        let proxy = &file.ext_authz["oauth2_proxy"];
+        assert_eq!(proxy.mode, ExtAuthzMode::Http); // default
+        assert_eq!(proxy.timeout, "1s"); // default
+        assert!(!proxy.failure_mode_allow);
+        assert_eq!(proxy.allowed_request_headers.len(), 2);
+        let opa = &file.ext_authz["opa"];
+        assert_eq!(opa.mode, ExtAuthzMode::Grpc);
+        assert_eq!(opa.timeout, "250ms");
+        assert!(opa.failure_mode_allow);
+
+        let route: RouteSpec = serde_yaml::from_str(
+            r#"{ match: { prefix: "/" }, to_upstream: "web", ext_authz: { disabled: true } }"#,
+        )
+        .unwrap();
+        assert!(route.ext_authz.unwrap().disabled);
+    }
+
+    #[test]
+    fn test_deserialize_access_policies() {
+        let yaml = r#"
+access:
+  office_vpn:
+    source_cidrs: ["10.8.0.0/16", "192.0.2.10"]
+    enforce_l4: true
+  block_scrapers:
+    action: DENY
+    source_cidrs: ["203.0.113.0/24"]
+    except_cidrs: ["203.0.113.7"]
+    headers:
+      - { name: "user-agent", exact_match: "BadBot/1.0" }
+"#;
+        let file: AccessPolicyFile = serde_yaml::from_str(yaml).unwrap();
+        let vpn = &file.access["office_vpn"];
+        assert_eq!(vpn.action, RbacAction::Allow); // default
+        assert_eq!(vpn.source_cidrs.len(), 2);
+        assert!(vpn.enforce_l4);
+        let scrapers = &file.access["block_scrapers"];
+        assert_eq!(scrapers.action, RbacAction::Deny);
+        assert_eq!(scrapers.source_cidrs, vec!["203.0.113.0/24".to_string()]);
+        assert_eq!(scrapers.except_cidrs, vec!["203.0.113.7".to_string()]);
+        assert_eq!(scrapers.headers[0].name, "user-agent");
+        assert!(!scrapers.enforce_l4);
+    }
+
+    #[test]
+    fn test_deserialize_compression_policies() {
+        let yaml = r#"
+compression:
+  brotli_text:
+    algorithm: brotli
+    level: 5
+    content_types: ["application/json", "text/html"]
+  gzip_default:
+    algorithm: gzip
+    min_content_length: 1024
+"#;
+        let file: CompressionPolicyFile = serde_yaml::from_str(yaml).unwrap();
+        let brotli = &file.compression["brotli_text"];
+        assert_eq!(brotli.algorithm, CompressionAlgorithm::Brotli);
+        assert_eq!(brotli.level, Some(5));
+        assert_eq!(brotli.content_types.len(), 2);
+        let gzip = &file.compression["gzip_default"];
+        assert_eq!(gzip.min_content_length, Some(1024));
+        assert!(gzip.algorithm.levels().contains(&9));
+        assert!(!gzip.algorithm.levels().contains(&11));
+
+        let bad: Result<CompressionPolicyFile, _> =
+            serde_yaml::from_str("compression: { x: { algorithm: lzma } }");
+        assert!(bad.is_err());
+    }
+
+    #[test]
+    fn test_deserialize_access_log_json_with_filter() {
+        let yaml = r#"
+type: stderr
+json_format:
+  status: "%RESPONSE_CODE%"
+  path: "%REQ(:PATH)%"
+filter:
+  status_codes:
+    - { min: 400, max: 599 }
+  sample_percent: 10
+  not_health_check: true
+"#;
+        let log: AccessLogSpec = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(log.r#type, AccessLogType::Stderr);
+        assert_eq!(log.path, "/dev/stdout");
+        let json = log.json_format.unwrap();
+        assert_eq!(json.keys().collect::<Vec<_>>(), vec!["status", "path"]);
+        let filter = log.filter.unwrap();
+        assert_eq!(filter.status_codes[0].min, Some(400));
+        assert_eq!(filter.sample_percent, Some(10));
+        assert!(filter.not_health_check);
+
+        let bad: Result<AccessLogSpec, _> = serde_yaml::from_str("type: syslog");
+        assert!(bad.is_err());
+    }
+
+    #[test]
+    fn test_deserialize_access_log_with_remote_sinks() {
+        let yaml = r#"
+type: stdout
+sinks:
+  - type: open_telemetry
+    upstream: otel_collector
+    log_name: edge
+    format: "%REQ(:METHOD)% %REQ(:PATH)%"
+  - type: grpc
+    upstream: als
+"#;
+        let log: AccessLogSpec = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(log.sinks.len(), 2);
+        assert_eq!(log.sinks[0].r#type, AccessLogType::OpenTelemetry);
+        assert_eq!(log.sinks[0].upstream.as_deref(), Some("otel_collector"));
+        assert_eq!(log.sinks[0].log_name.as_deref(), Some("edge"));
+        assert_eq!(log.sinks[1].r#type, AccessLogType::Grpc);
+        assert!(log.sinks[1].r#type.is_remote());
+        assert!(!log.r#type.is_remote());
+    }
+
     #[test]
     fn test_deserialize_policies_spec_empty() {
         let yaml = "{}";
@@ -0,0 +1,62 @@
+//! Minimal valid specs shared by the unit tests. Tests override only the
+//! fields they exercise, e.g. `UpstreamSpec { http2: true, ..upstream(..) }`.
+
+use crate::load::Loaded;
+use crate::model::*;
+
+/// STRICT_DNS, round-robin upstream with a single endpoint
+pub fn upstream(name: &str, address: &str, port: u16) -> UpstreamSpec {
+    UpstreamSpec {
+        name: name.to_string(),
+        connect_timeout: "5s".to_string(),
+        r#type: ClusterType::StrictDns,
+        lb_policy: LbPolicy::RoundRobin,
+        endpoints: vec![Endpoint {
+            address: address.to_string(),
+            port,
+            ..Default::default()
+        }],
+        ..Default::default()
+    }
+}
+
+pub fn defaults(http_default_upstream: &str, tls_passthrough_upstream: &str) -> DefaultsSpec {
+    DefaultsSpec {
+        route_timeout: "60s".to_string(),
+        http_default_upstream: http_default_upstream.to_string(),
+        tls_passthrough_upstream: tls_passthrough_upstream.to_string(),
+        ..Default::default()
+    }
+}
+
+pub fn stdout_log() -> AccessLogSpec {
+    AccessLogSpec {
+        r#type: AccessLogType::Stdout,
+        path: "/dev/stdout".to_string(),
+        ..Default::default()
+    }
+}
+
+/// Admin on 127.0.0.1:9901, both default upstreams named `web`, nothing else
+pub fn loaded() -> Loaded {
+    Loaded {
+        admin: AdminSpec {
+            address: "127.0.0.1".to_string(),
+            port: 9901,
+            ..Default::default()
+        },
+        defaults: defaults("web", "web"),
+        access_log: stdout_log(),
+        tracing: None,
+        stats: StatsSpec::default(),
+        overload: OverloadSpec::default(),
+        validate: ValidateSpec::DockerImage {
+            image: "envoyproxy/envoy:v1.31-latest".to_string(),
+        },
+        listeners: ListenersSpec::default(),
+        domains: vec![],
+        upstreams: vec![],
+        policies: PoliciesSpec::default(),
+        certs: vec![],
+    }
+}
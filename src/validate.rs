@@ -1,13 +1,27 @@
+use crate::generate::sanitize_name;
+use crate::load::Loaded;
 use crate::model::*;
 use anyhow::{bail, Result};
 use std::collections::{HashMap, HashSet};
+use std::net::IpAddr;
 
 pub fn validate_model(
     domains: &[DomainSpec],
     upstreams: &[UpstreamSpec],
     policies: &PoliciesSpec,
     defaults: &DefaultsSpec,
+    cert_sets: &[CertSetSpec],
 ) -> Result<()> {
+    let mut seen_cert_sets = HashSet::new();
+    for c in cert_sets {
+        if !seen_cert_sets.insert(c.name.as_str()) {
+            bail!("duplicate certificate set: {}", c.name);
+        }
+        if c.certificates.is_empty() {
+            bail!("certificate set {} has no certificates", c.name);
+        }
+    }
+
     let mut seen_domains = HashSet::new();
     for d in domains {
         if !seen_domains.insert(d.domain.as_str()) {
@@ -23,8 +37,87 @@ pub fn validate_model(
         if d.mode != "terminate_https_443" && d.mode != "passthrough_https_443" {
             bail!("domain {} has unsupported mode: {}", d.domain, d.mode);
         }
+
+        if let Some(tls) = &d.tls {
+            if tls.cert_chain.is_some() != tls.private_key.is_some() {
+                bail!(
+                    "domain {} tls requires both cert_chain and private_key",
+                    d.domain
+                );
+            }
+            if let Some(name) = &tls.cert_set {
+                if !seen_cert_sets.contains(name.as_str()) {
+                    bail!(
+                        "domain {} references unknown certificate set {}",
+                        d.domain,
+                        name
+                    );
+                }
+            }
+            if tls.resolved_certificates(cert_sets).is_empty() {
+                bail!(
+                    "domain {} tls has no certificates (set cert_chain/private_key, certificates or cert_set)",
+                    d.domain
+                );
+            }
+
+            let params = tls.params.resolve(&defaults.tls);
+            if let (Some(min), Some(max)) = (
+                params.tls_minimum_protocol_version,
+                params.tls_maximum_protocol_version,
+            ) {
+                if min != TlsProtocolVersion::TlsAuto
+                    && max != TlsProtocolVersion::TlsAuto
+                    && min > max
+                {
+                    bail!(
+                        "domain {} tls_minimum_protocol_version {} is above tls_maximum_protocol_version {}",
+                        d.domain,
+                        min.as_str(),
+                        max.as_str()
+                    );
+                }
+            }
+            if tls.client_ca.is_none() {
+                if tls.require_client_certificate {
+                    bail!(
+                        "domain {} tls.require_client_certificate requires tls.client_ca",
+                        d.domain
+                    );
+                }
+                if !tls.verify_san.is_empty()
+                    || !tls.verify_spki.is_empty()
+                    || !tls.verify_hash.is_empty()
+                    || tls.crl.is_some()
+                {
+                    bail!(
+                        "domain {} client certificate verification requires tls.client_ca",
+                        d.domain
+                    );
+                }
+            }
+        }
+        if let Some(hcm) = &d.http_connection_manager {
+            // Envoy only honours set_current_client_cert_details in these two modes
+            if hcm.set_current_client_cert_details.is_some()
+                && !matches!(
+                    hcm.forward_client_cert_details,
+                    Some(
+                        ForwardClientCertDetails::AppendForward
+                            | ForwardClientCertDetails::SanitizeSet
+                    )
+                )
+            {
+                bail!(
+                    "domain {} set_current_client_cert_details requires forward_client_cert_details APPEND_FORWARD or SANITIZE_SET",
+                    d.domain
+                );
+            }
+        }
     }
 
+    check_listener_limits(&defaults.listener_limits, "defaults.listener_limits")?;
+
     let upstream_map: HashMap<_, _> = upstreams.iter().map(|u| (u.name.as_str(), u)).collect();
 
     if !upstream_map.contains_key(defaults.http_default_upstream.as_str()) {
@@ -41,13 +134,491 @@ pub fn validate_model(
     }
 
     for u in upstreams {
-        if u.endpoints.is_empty() {
-            bail!("upstream {} has no endpoints", u.name);
+        if u.r#type == ClusterType::Eds {
+            if u.eds_config_path.is_none() {
+                bail!("upstream {} type EDS requires eds_config_path", u.name);
+            }
+            if u.all_endpoints().next().is_some() {
+                bail!(
+                    "upstream {} type EDS takes its endpoints from eds_config_path; remove endpoints/localities",
+                    u.name
+                );
+            }
+        } else {
+            if u.eds_config_path.is_some() {
+                bail!("upstream {} eds_config_path requires type EDS", u.name);
+            }
+            if u.all_endpoints().next().is_none() {
+                bail!("upstream {} has no endpoints", u.name);
+            }
+        }
+        if u.r#type == ClusterType::Static {
+            if let Some(ep) = u
+                .all_endpoints()
+                .find(|ep| ep.address.parse::<IpAddr>().is_err())
+            {
+                bail!(
+                    "upstream {} type STATIC requires IP addresses, got hostname {} (use STRICT_DNS)",
+                    u.name,
+                    ep.address
+                );
+            }
+        }
+        if u.r#type == ClusterType::LogicalDns && u.all_endpoints().count() > 1 {
+            bail!(
+                "upstream {} type LOGICAL_DNS supports a single endpoint (use STRICT_DNS for several)",
+                u.name
+            );
+        }
+        if !u.r#type.resolves_dns()
+            && (u.dns_lookup_family.is_some()
+                || u.dns_refresh_rate.is_some()
+                || u.respect_dns_ttl.is_some())
+        {
+            bail!(
+                "upstream {} DNS options require type STRICT_DNS or LOGICAL_DNS, got {}",
+                u.name,
+                u.r#type.as_str()
+            );
+        }
+        if u.all_endpoints().any(|ep| ep.weight == Some(0)) {
+            bail!("upstream {} endpoint weight must be at least 1", u.name);
+        }
+        for l in &u.localities {
+            if l.endpoints.is_empty() {
+                bail!(
+                    "upstream {} has a locality at priority {} with no endpoints",
+                    u.name,
+                    l.priority
+                );
+            }
+        }
+        // Envoy rejects gaps in the priority list, so they must run 0..N
+        let mut priorities: Vec<u32> = u.localities.iter().map(|l| l.priority).collect();
+        if !u.endpoints.is_empty() {
+            priorities.push(0);
+        }
+        priorities.sort_unstable();
+        priorities.dedup();
+        if priorities.iter().enumerate().any(|(i, p)| *p != i as u32) {
+            bail!(
+                "upstream {} locality priorities must be contiguous starting at 0, got {:?}",
+                u.name,
+                priorities
+            );
+        }
+        let weighted = u
+            .localities
+            .iter()
+            .filter(|l| l.load_balancing_weight.is_some())
+            .count();
+        if weighted > 0 && (weighted != u.localities.len() || !u.endpoints.is_empty()) {
+            bail!(
+                "upstream {} sets load_balancing_weight on some localities; every endpoint must then belong to a weighted locality",
+                u.name
+            );
+        }
+        if let Some(pct) = u
+            .outlier_detection
+            .as_ref()
+            .and_then(|od| od.max_ejection_percent)
+        {
+            if pct > 100 {
+                bail!(
+                    "upstream {} outlier_detection.max_ejection_percent must be 0-100, got {}",
+                    u.name,
+                    pct
+                );
+            }
+        }
+        if let Some(tls) = &u.tls {
+            if tls.client_cert_chain.is_some() != tls.client_private_key.is_some() {
+                bail!(
+                    "upstream {} tls requires both client_cert_chain and client_private_key",
+                    u.name
+                );
+            }
+            if !tls.verify_san.is_empty() && tls.trusted_ca.is_none() {
+                bail!("upstream {} tls verify_san requires trusted_ca", u.name);
+            }
+            match (&tls.trusted_ca, tls.insecure_skip_verify) {
+                (None, false) => bail!(
+                    "upstream {} tls has no trusted_ca, so the backend certificate would not be verified; set insecure_skip_verify: true to allow this",
+                    u.name
+                ),
+                (Some(_), true) => bail!(
+                    "upstream {} tls sets both trusted_ca and insecure_skip_verify",
+                    u.name
+                ),
+                _ => {}
+            }
+        }
+
+        for (block, set, policy) in [
+            (
+                "least_request",
+                u.least_request.is_some(),
+                LbPolicy::LeastRequest,
+            ),
+            ("ring_hash", u.ring_hash.is_some(), LbPolicy::RingHash),
+            ("maglev", u.maglev.is_some(), LbPolicy::Maglev),
+        ] {
+            if set && u.lb_policy != policy {
+                bail!(
+                    "upstream {} {} options require lb_policy {}",
+                    u.name,
+                    block,
+                    policy.as_str()
+                );
+            }
+        }
+        if let Some(rh) = &u.ring_hash {
+            if let (Some(min), Some(max)) = (rh.minimum_ring_size, rh.maximum_ring_size) {
+                if min > max {
+                    bail!(
+                        "upstream {} ring_hash.minimum_ring_size {} is above maximum_ring_size {}",
+                        u.name,
+                        min,
+                        max
+                    );
+                }
+            }
+        }
+        if let Some(size) = u.maglev.as_ref().and_then(|m| m.table_size) {
+            if size > MAGLEV_MAX_TABLE_SIZE {
+                bail!(
+                    "upstream {} maglev.table_size must be at most {}, got {}",
+                    u.name,
+                    MAGLEV_MAX_TABLE_SIZE,
+                    size
+                );
+            }
+            if !is_prime(size) {
+                bail!(
+                    "upstream {} maglev.table_size must be a prime number, got {}",
+                    u.name,
+                    size
+                );
+            }
+        }
+    }
+
+    for (name, c) in &policies.cors {
+        if c.allow_origins.is_empty() {
+            bail!("cors policy {} has no allow_origins", name);
+        }
+        if !c.allow_origins.iter().all(StringMatchSpec::is_valid) {
+            bail!(
+                "cors policy {} allow_origins entries need exactly one of exact, prefix or regex",
+                name
+            );
+        }
+    }
+
+    for (name, p) in &policies.jwt_providers {
+        if p.local_jwks.is_some() == p.remote_jwks.is_some() {
+            bail!(
+                "jwt provider {} needs exactly one of local_jwks or remote_jwks",
+                name
+            );
+        }
+        if let Some(remote) = &p.remote_jwks {
+            if !upstream_map.contains_key(remote.upstream.as_str()) {
+                bail!(
+                    "jwt provider {} remote_jwks references unknown upstream {}",
+                    name,
+                    remote.upstream
+                );
+            }
+        }
+    }
+
+    for (name, e) in &policies.ext_authz {
+        let Some(u) = upstream_map.get(e.upstream.as_str()) else {
+            bail!(
+                "ext_authz policy {} references unknown upstream {}",
+                name,
+                e.upstream
+            );
+        };
+        if e.mode == ExtAuthzMode::Grpc && !u.uses_http2() {
+            bail!(
+                "ext_authz policy {} uses grpc mode but upstream {} does not enable http2",
+                name,
+                e.upstream
+            );
+        }
+        if e.mode == ExtAuthzMode::Grpc
+            && (e.path_prefix.is_some() || !e.allowed_upstream_headers.is_empty())
+        {
+            bail!(
+                "ext_authz policy {} path_prefix and allowed_upstream_headers only apply to http mode",
+                name
+            );
+        }
+    }
+
+    for (name, tb) in &policies.local_ratelimits {
+        for (field, percent) in [
+            ("filter_enabled", tb.filter_enabled),
+            ("filter_enforced", tb.filter_enforced),
+        ] {
+            if percent.is_some_and(|p| p > 100) {
+                bail!(
+                    "local_ratelimit {} {} must be between 0 and 100",
+                    name,
+                    field
+                );
+            }
+        }
+        if let Some(code) = tb.status {
+            if !(400..=599).contains(&code) {
+                bail!(
+                    "local_ratelimit {} status {} must be a 4xx or 5xx code",
+                    name,
+                    code
+                );
+            }
+        }
+        let mut seen = HashSet::new();
+        for d in &tb.descriptors {
+            if d.header.is_some() == d.remote_address {
+                bail!(
+                    "local_ratelimit {} descriptors must each set exactly one of header or remote_address",
+                    name
+                );
+            }
+            if d.remote_address && d.value.parse::<IpAddr>().is_err() {
+                bail!(
+                    "local_ratelimit {} remote_address descriptor value {} is not an IP address",
+                    name,
+                    d.value
+                );
+            }
+            if !seen.insert((d.key(), d.value.as_str())) {
+                bail!(
+                    "local_ratelimit {} has duplicate descriptor {}={}",
+                    name,
+                    d.key(),
+                    d.value
+                );
+            }
+        }
+    }
+
+    if let Some(service) = &policies.ratelimit_service {
+        let Some(u) = upstream_map.get(service.upstream.as_str()) else {
+            bail!(
+                "ratelimit_service references unknown upstream {}",
+                service.upstream
+            );
+        };
+        if !u.uses_http2() {
+            bail!(
+                "ratelimit_service upstream {} must enable http2 (the service speaks gRPC)",
+                service.upstream
+            );
+        }
+        if service.domain.is_empty() {
+            bail!("ratelimit_service domain must not be empty");
+        }
+    } else if !policies.global_ratelimits.is_empty() {
+        bail!("global_ratelimits require a ratelimit_service");
+    }
+    for (name, g) in &policies.global_ratelimits {
+        if g.actions.is_empty() {
+            bail!("global_ratelimit {} needs at least one action", name);
+        }
+        if !g.actions.iter().all(RateLimitActionSpec::is_valid) {
+            bail!(
+                "global_ratelimit {} actions must each set exactly one of remote_address, request_headers or generic_key",
+                name
+            );
+        }
+    }
+
+    for (name, a) in &policies.access {
+        if a.source_cidrs.is_empty() && a.except_cidrs.is_empty() && a.headers.is_empty() {
+            bail!(
+                "access policy {} needs source_cidrs, except_cidrs or headers",
+                name
+            );
+        }
+        if let Some(cidr) = a
+            .source_cidrs
+            .iter()
+            .chain(&a.except_cidrs)
+            .find(|c| !is_valid_cidr(c))
+        {
+            bail!("access policy {} has invalid CIDR {}", name, cidr);
+        }
+        if a.enforce_l4 && (a.action != RbacAction::Allow || a.source_cidrs.is_empty()) {
+            bail!(
+                "access policy {} enforce_l4 requires action ALLOW and source_cidrs",
+                name
+            );
+        }
+    }
+
+    for (name, c) in &policies.compression {
+        if let Some(level) = c.level {
+            let range = c.algorithm.levels();
+            if !range.contains(&level) {
+                bail!(
+                    "compression policy {} level {} is out of range for {} ({}-{})",
+                    name,
+                    level,
+                    c.algorithm.as_str(),
+                    range.start(),
+                    range.end()
+                );
+            }
         }
     }
 
     for d in domains {
+        let mut algorithms = HashSet::new();
+        for name in &d.compression {
+            let Some(c) = policies.compression.get(name) else {
+                bail!(
+                    "domain {} references unknown compression policy {}",
+                    d.domain,
+                    name
+                );
+            };
+            if !algorithms.insert(c.algorithm.as_str()) {
+                bail!(
+                    "domain {} uses more than one {} compression policy",
+                    d.domain,
+                    c.algorithm.as_str()
+                );
+            }
+        }
+        if let Some(name) = &d.local_ratelimit {
+            if !policies.local_ratelimits.contains_key(name) {
+                bail!(
+                    "domain {} references unknown local_ratelimit policy {}",
+                    d.domain,
+                    name
+                );
+            }
+        }
+        if let Some(name) = &d.access {
+            if !policies.access.contains_key(name) {
+                bail!(
+                    "domain {} references unknown access policy {}",
+                    d.domain,
+                    name
+                );
+            }
+        }
+        if let Some(name) = &d.ext_authz {
+            if !policies.ext_authz.contains_key(name) {
+                bail!(
+                    "domain {} references unknown ext_authz policy {}",
+                    d.domain,
+                    name
+                );
+            }
+        }
+        if let Some(jwt) = &d.jwt {
+            if jwt.disabled {
+                bail!(
+                    "domain {} jwt.disabled is only valid on routes; remove the domain jwt block instead",
+                    d.domain
+                );
+            }
+            check_jwt_provider(&d.domain, jwt, policies)?;
+        }
+        if let Some(name) = &d.cors {
+            if !policies.cors.contains_key(name) {
+                bail!(
+                    "domain {} references unknown cors policy {}",
+                    d.domain,
+                    name
+                );
+            }
+        }
         for r in &d.routes {
+            if r.connect {
+                if r.m.prefix.is_some() || r.m.path.is_some() {
+                    bail!(
+                        "domain {} connect route matches all CONNECT requests; remove match prefix/path",
+                        d.domain
+                    );
+                }
+                if r.websocket {
+                    bail!(
+                        "domain {} route cannot set both websocket and connect",
+                        d.domain
+                    );
+                }
+            }
+            if (r.websocket || r.connect) && r.direct_response.is_some() {
+                bail!(
+                    "domain {} websocket/connect route needs to_upstream, not direct_response",
+                    d.domain
+                );
+            }
+            for (field, value) in [
+                ("max_grpc_timeout", &r.max_grpc_timeout),
+                ("grpc_timeout_header_offset", &r.grpc_timeout_header_offset),
+            ] {
+                if let Some(v) = value {
+                    if duration_secs(v).is_none() {
+                        bail!(
+                            "domain {} route {} {} {} is not a duration (e.g. 30s)",
+                            d.domain,
+                            route_label(r),
+                            field,
+                            v
+                        );
+                    }
+                }
+            }
+            if let Some(name) = &r.access {
+                match policies.access.get(name) {
+                    None => bail!(
+                        "domain {} route references unknown access policy {}",
+                        d.domain,
+                        name
+                    ),
+                    // A route cannot narrow the TLS filter chain of its domain
+                    Some(a) if a.enforce_l4 => bail!(
+                        "domain {} route uses access policy {} with enforce_l4, which only applies to domains",
+                        d.domain,
+                        name
+                    ),
+                    Some(_) => {}
+                }
+            }
+            if let Some(jwt) = &r.jwt {
+                if !jwt.bypass_paths.is_empty() {
+                    bail!(
+                        "domain {} jwt.bypass_paths is only valid on the domain, not on routes",
+                        d.domain
+                    );
+                }
+                if jwt.disabled {
+                    if jwt.provider.is_some() {
+                        bail!(
+                            "domain {} route jwt sets both disabled and provider",
+                            d.domain
+                        );
+                    }
+                } else {
+                    check_jwt_provider(&d.domain, jwt, policies)?;
+                }
+            }
+            if let Some(name) = &r.cors {
+                if !policies.cors.contains_key(name) {
+                    bail!(
+                        "domain {} route references unknown cors policy {}",
+                        d.domain,
+                        name
+                    );
+                }
+            }
             // Only validate upstream reference if this is not a direct_response route
             if let Some(upstream) = &r.to_upstream {
                 if !upstream_map.contains_key(upstream.as_str()) {
@@ -58,6 +629,15 @@ pub fn validate_model(
                     );
                 }
             }
+            for h in &r.hash_policy {
+                let kinds = [h.header.is_some(), h.cookie.is_some(), h.source_ip];
+                if kinds.iter().filter(|k| **k).count() != 1 {
+                    bail!(
+                        "domain {} hash_policy entries need exactly one of header, cookie or source_ip",
+                        d.domain
+                    );
+                }
+            }
             if let Some(pfc) = &r.per_filter_config {
                 if let Some(key) = &pfc.local_ratelimit {
                     if !policies.local_ratelimits.contains_key(key) {
@@ -68,6 +648,15 @@ pub fn validate_model(
                         );
                     }
                 }
+                for key in &pfc.global_ratelimits {
+                    if !policies.global_ratelimits.contains_key(key) {
+                        bail!(
+                            "domain {} route references unknown global_ratelimit policy {}",
+                            d.domain,
+                            key
+                        );
+                    }
+                }
             }
         }
     }
@@ -75,507 +664,2422 @@ pub fn validate_model(
     Ok(())
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// Validate everything that was loaded: the model plus the access logs, whose
+/// collector sinks need the upstream list.
+pub fn validate_loaded(loaded: &Loaded) -> Result<()> {
+    validate_model(
+        &loaded.domains,
+        &loaded.upstreams,
+        &loaded.policies,
+        &loaded.defaults,
+        &loaded.certs,
+    )?;
 
-    #[test]
-    fn test_validate_model_success() {
-        let domains = vec![DomainSpec {
-            domain: "example.com".to_string(),
-            mode: "terminate_https_443".to_string(),
-            tls: Some(TlsSpec {
-                cert_chain: "/path/to/cert".to_string(),
-                private_key: "/path/to/key".to_string(),
-            }),
-            routes: vec![RouteSpec {
-                m: MatchSpec {
-                    prefix: Some("/api".to_string()),
-                    path: None,
-                    headers: vec![],
-                },
-                to_upstream: Some("api_backend".to_string()),
-                timeout: Some("30s".to_string()),
-                per_filter_config: None,
-                prefix_rewrite: None,
-                direct_response: None,
-            }],
-            http_connection_manager: None,
-            normalize_path: None,
-            merge_slashes: None,
-            aws_signing: None,
-        }];
+    validate_access_log(&loaded.access_log, "access_log", &loaded.upstreams)?;
+    for d in &loaded.domains {
+        if let Some(log) = &d.access_log {
+            let context = format!("domain {} access_log", d.domain);
+            validate_access_log(log, &context, &loaded.upstreams)?;
+        }
+    }
+    for l in &loaded.listeners.internal_http_listeners {
+        if let Some(limits) = &l.limits {
+            check_listener_limits(limits, &format!("listener {} limits", l.name))?;
+        }
+        if let Some(log) = &l.access_log {
+            let context = format!("listener {} access_log", l.name);
+            validate_access_log(log, &context, &loaded.upstreams)?;
+        }
+    }
 
-        let upstreams = vec![
-            UpstreamSpec {
-                name: "api_backend".to_string(),
-                connect_timeout: "5s".to_string(),
-                r#type: "STRICT_DNS".to_string(),
-                lb_policy: "ROUND_ROBIN".to_string(),
-                http2: false,
-                endpoints: vec![Endpoint {
-                    address: "127.0.0.1".to_string(),
-                    port: 8080,
-                }],
-            },
-            UpstreamSpec {
-                name: "cilium_http".to_string(), // default http upstream
-                connect_timeout: "5s".to_string(),
-                r#type: "STRICT_DNS".to_string(),
-                lb_policy: "ROUND_ROBIN".to_string(),
-                http2: false,
-                endpoints: vec![Endpoint {
-                    address: "127.0.0.1".to_string(),
-                    port: 80,
-                }],
-            },
-            UpstreamSpec {
-                name: "cilium_tls".to_string(), // default tls upstream
-                connect_timeout: "5s".to_string(),
-                r#type: "STRICT_DNS".to_string(),
-                lb_policy: "ROUND_ROBIN".to_string(),
-                http2: false,
-                endpoints: vec![Endpoint {
-                    address: "127.0.0.1".to_string(),
-                    port: 443,
-                }],
-            },
-        ];
+    match &loaded.tracing {
+        Some(tracing) => validate_tracing(tracing, &loaded.domains, &loaded.upstreams)?,
+        None => {
+            if let Some(d) = loaded.domains.iter().find(|d| d.tracing.is_some()) {
+                bail!(
+                    "domain {} sets tracing but common/tracing.yaml is missing",
+                    d.domain
+                );
+            }
+        }
+    }
 
-        let policies = PoliciesSpec {
-            local_ratelimits: Default::default(),
-        };
+    validate_stats(&loaded.stats, &loaded.upstreams)?;
+    check_stat_prefixes(loaded)?;
+    validate_builtin_listeners(loaded)?;
+    validate_admin(loaded)?;
+    validate_overload(&loaded.overload)?;
+    Ok(())
+}
 
-        let defaults = DefaultsSpec {
-            route_timeout: "60s".to_string(),
-            http_default_upstream: "cilium_http".to_string(),
-            tls_passthrough_upstream: "cilium_tls".to_string(),
-        };
+fn validate_overload(overload: &OverloadSpec) -> Result<()> {
+    for (field, value) in [
+        ("max_heap_size_bytes", overload.max_heap_size_bytes),
+        (
+            "max_active_downstream_connections",
+            overload.max_active_downstream_connections,
+        ),
+        (
+            "global_downstream_max_connections",
+            overload.global_downstream_max_connections,
+        ),
+    ] {
+        if value == Some(0) {
+            bail!("overload {} must be at least 1", field);
+        }
+    }
+    if overload.max_active_downstream_connections.is_some()
+        && overload.global_downstream_max_connections.is_some()
+    {
+        bail!("overload sets both max_active_downstream_connections and global_downstream_max_connections; pick one");
+    }
+    if !overload.actions.is_empty() && overload.max_heap_size_bytes.is_none() {
+        bail!("overload actions trigger on heap usage and need max_heap_size_bytes");
+    }
+    let mut seen = HashSet::new();
+    for a in &overload.actions {
+        let name = a.action.as_str();
+        if !seen.insert(name) {
+            bail!("overload action {} is listed more than once", name);
+        }
+        if !(a.heap_threshold > 0.0 && a.heap_threshold <= 1.0) {
+            bail!(
+                "overload action {} heap_threshold {} must be above 0 and at most 1",
+                name,
+                a.heap_threshold
+            );
+        }
+    }
+    if overload.refresh_interval.is_some()
+        && overload.max_heap_size_bytes.is_none()
+        && overload.max_active_downstream_connections.is_none()
+    {
+        bail!("overload refresh_interval has no resource monitor to refresh");
+    }
+    Ok(())
+}
+
+/// Enabled built-in listeners with the port each one binds.
+fn builtin_listener_ports(defaults: &DefaultsSpec) -> Vec<(u16, &'static str)> {
+    let mut ports = Vec::new();
+    if defaults.http_listener.enabled {
+        ports.push((defaults.http_port(), "defaults.http_listener"));
+    }
+    if defaults.https_listener.enabled {
+        ports.push((defaults.https_port(), "defaults.https_listener"));
+    }
+    ports
+}
+
+fn validate_builtin_listeners(loaded: &Loaded) -> Result<()> {
+    let defaults = &loaded.defaults;
+    for (name, spec, port) in [
+        (
+            "http_listener",
+            &defaults.http_listener,
+            defaults.http_port(),
+        ),
+        (
+            "https_listener",
+            &defaults.https_listener,
+            defaults.https_port(),
+        ),
+    ] {
+        if !spec.enabled {
+            continue;
+        }
+        if port == 0 {
+            bail!("defaults.{} port must not be 0", name);
+        }
+        match spec.address.parse::<IpAddr>() {
+            Ok(IpAddr::V6(_)) => {}
+            Ok(IpAddr::V4(_)) if spec.ipv4_compat => bail!(
+                "defaults.{} ipv4_compat needs an IPv6 address such as ::, got {}",
+                name,
+                spec.address
+            ),
+            Ok(IpAddr::V4(_)) => {}
+            Err(_) => bail!(
+                "defaults.{} address {} is not an IP address",
+                name,
+                spec.address
+            ),
+        }
+        let mut seen = HashSet::from([(spec.address.as_str(), port)]);
+        for extra in &spec.additional_addresses {
+            let extra_port = extra.port.unwrap_or(port);
+            if extra.address.parse::<IpAddr>().is_err() {
+                bail!(
+                    "defaults.{} additional address {} is not an IP address",
+                    name,
+                    extra.address
+                );
+            }
+            if extra_port == 0 {
+                bail!("defaults.{} additional address port must not be 0", name);
+            }
+            if !seen.insert((extra.address.as_str(), extra_port)) {
+                bail!(
+                    "defaults.{} binds address {} port {} more than once",
+                    name,
+                    extra.address,
+                    extra_port
+                );
+            }
+        }
+    }
+
+    let ports = builtin_listener_ports(defaults);
+    if let [(http, _), (https, _)] = ports.as_slice() {
+        if http == https {
+            bail!(
+                "defaults.http_listener and defaults.https_listener both use port {}",
+                http
+            );
+        }
+    }
+    for l in &loaded.listeners.internal_http_listeners {
+        if let Some((_, owner)) = ports.iter().find(|(port, _)| *port == l.port) {
+            bail!(
+                "listener {} port {} is already used by {}",
+                l.name,
+                l.port,
+                owner
+            );
+        }
+    }
+
+    if !defaults.https_listener.enabled {
+        if let Some(d) = loaded.domains.first() {
+            bail!(
+                "domain {} needs the HTTPS listener but defaults.https_listener is disabled",
+                d.domain
+            );
+        }
+    }
+    Ok(())
+}
+
+fn validate_admin(loaded: &Loaded) -> Result<()> {
+    let admin = &loaded.admin;
+    if admin.socket_path.as_deref() == Some("") {
+        bail!("admin socket_path must not be empty");
+    }
+    if admin.profile_path.as_deref() == Some("") {
+        bail!("admin profile_path must not be empty");
+    }
+    if let Some(log) = &admin.access_log {
+        validate_access_log(log, "admin access_log", &loaded.upstreams)?;
+    }
+    if let Some(readonly) = &admin.readonly_listener {
+        if loaded.upstreams.iter().any(|u| u.name == "envoy_admin") {
+            bail!("upstream name envoy_admin is reserved for admin readonly_listener");
+        }
+        let mut taken = builtin_listener_ports(&loaded.defaults);
+        if admin.socket_path.is_none() {
+            taken.push((admin.port, "the admin interface"));
+        }
+        for l in &loaded.listeners.internal_http_listeners {
+            taken.push((l.port, l.name.as_str()));
+        }
+        if let Some((_, owner)) = taken.iter().find(|(port, _)| *port == readonly.port) {
+            bail!(
+                "admin readonly_listener port {} is already used by {}",
+                readonly.port,
+                owner
+            );
+        }
+    }
+    Ok(())
+}
+
+fn validate_stats(stats: &StatsSpec, upstreams: &[UpstreamSpec]) -> Result<()> {
+    for (i, sink) in stats.sinks.iter().enumerate() {
+        let context = format!("stats sinks[{}] ({})", i, sink.r#type.as_str());
+        match (&sink.upstream, &sink.address) {
+            (Some(_), Some(_)) => bail!("{} sets both upstream and address", context),
+            (None, None) => bail!("{} needs an address and port or an upstream", context),
+            (Some(name), None) => {
+                if sink.r#type != StatsSinkType::Statsd {
+                    bail!("{} only supports address, not upstream", context);
+                }
+                if !upstreams.iter().any(|u| &u.name == name) {
+                    bail!("{} references unknown upstream {}", context, name);
+                }
+            }
+            (None, Some(address)) => {
+                if address.parse::<IpAddr>().is_err() {
+                    bail!("{} address {} must be an IP address", context, address);
+                }
+                if sink.port.is_none() {
+                    bail!("{} address needs a port", context);
+                }
+            }
+        }
+    }
+    for tag in &stats.tags {
+        if tag.regex.is_some() == tag.fixed_value.is_some() {
+            bail!(
+                "stats tag {} needs exactly one of regex or fixed_value",
+                tag.tag_name
+            );
+        }
+    }
+    if !stats.include.is_empty() && !stats.exclude.is_empty() {
+        bail!("stats include and exclude cannot both be set");
+    }
+    if !stats
+        .include
+        .iter()
+        .chain(&stats.exclude)
+        .all(StringMatchSpec::is_valid)
+    {
+        bail!("stats include/exclude entries need exactly one of exact, prefix or regex");
+    }
+    Ok(())
+}
+
+/// Stat prefixes chosen for connection managers and local rate limit buckets
+/// share one namespace in dashboards, so each must belong to a single owner.
+/// Domains that use the same bucket share its counters on purpose.
+fn check_stat_prefixes(loaded: &Loaded) -> Result<()> {
+    let mut owners: HashMap<String, String> = HashMap::new();
+    let mut claim = |prefix: String, owner: String| -> Result<()> {
+        match owners.get(&prefix) {
+            Some(existing) if *existing != owner => bail!(
+                "stat_prefix {} is used by both {} and {}",
+                prefix,
+                existing,
+                owner
+            ),
+            Some(_) => Ok(()),
+            None => {
+                owners.insert(prefix, owner);
+                Ok(())
+            }
+        }
+    };
+
+    let edge_limits = &loaded.defaults.listener_limits;
+    if loaded.defaults.http_listener.enabled {
+        claim("external_http".to_string(), "the HTTP listener".to_string())?;
+        for prefix in limit_stat_prefixes(edge_limits, "http_listener") {
+            claim(prefix, "the HTTP listener limits".to_string())?;
+        }
+    }
+    if loaded.defaults.https_listener.enabled {
+        claim(
+            "external_tls_passthrough".to_string(),
+            "the HTTPS passthrough chain".to_string(),
+        )?;
+        for prefix in limit_stat_prefixes(edge_limits, "https_sni_listener") {
+            claim(prefix, "the HTTPS listener limits".to_string())?;
+        }
+    }
+    if loaded.admin.readonly_listener.is_some() {
+        claim(
+            "admin_readonly".to_string(),
+            "admin readonly_listener".to_string(),
+        )?;
+    }
+    for d in loaded
+        .domains
+        .iter()
+        .filter(|d| d.mode == "terminate_https_443")
+    {
+        let hcm = d.http_connection_manager.as_ref();
+        let prefix = hcm
+            .and_then(|h| h.stat_prefix.clone())
+            .unwrap_or_else(|| format!("{}_https", sanitize_name(&d.domain)));
+        claim(prefix, format!("domain {}", d.domain))?;
+        if let Some(prefix) = hcm.and_then(|h| h.local_ratelimit_stat_prefix.clone()) {
+            claim(
+                prefix,
+                format!("domain {} local_ratelimit_stat_prefix", d.domain),
+            )?;
+        }
+    }
+    for l in &loaded.listeners.internal_http_listeners {
+        claim(l.stat_prefix.clone(), format!("listener {}", l.name))?;
+        let limits = l.limits.as_ref().unwrap_or(edge_limits);
+        for prefix in limit_stat_prefixes(limits, &l.stat_prefix) {
+            claim(prefix, format!("listener {} limits", l.name))?;
+        }
+    }
+    for (key, tb) in &loaded.policies.local_ratelimits {
+        let prefix = tb
+            .stat_prefix
+            .clone()
+            .unwrap_or_else(|| format!("rl_{}", key));
+        claim(prefix, format!("local_ratelimits.{}", key))?;
+    }
+    Ok(())
+}
+
+/// Stat prefixes of the network filters generated for `limits`
+fn limit_stat_prefixes(limits: &ListenerLimitsSpec, prefix: &str) -> Vec<String> {
+    let mut prefixes = Vec::new();
+    if limits.connection_rate_limit.is_some() {
+        prefixes.push(format!("{prefix}_conn_rl"));
+    }
+    if limits.max_connections.is_some() {
+        prefixes.push(format!("{prefix}_conn_limit"));
+    }
+    prefixes
+}
+
+fn validate_tracing(
+    tracing: &TracingSpec,
+    domains: &[DomainSpec],
+    upstreams: &[UpstreamSpec],
+) -> Result<()> {
+    let provider = tracing.provider.as_str();
+    match upstreams.iter().find(|u| u.name == tracing.upstream) {
+        None => bail!("tracing references unknown upstream {}", tracing.upstream),
+        Some(u) if tracing.provider == TracingProvider::OpenTelemetry && !u.uses_http2() => {
+            bail!(
+                "tracing provider {} needs gRPC but upstream {} does not enable http2",
+                provider,
+                u.name
+            )
+        }
+        Some(_) => {}
+    }
+    if tracing.service_name.is_empty() {
+        bail!("tracing service_name must not be empty");
+    }
+    if tracing.collector_endpoint.is_some() && tracing.provider != TracingProvider::Zipkin {
+        bail!(
+            "tracing collector_endpoint only applies to zipkin, not {}",
+            provider
+        );
+    }
+    check_tracing_overrides(&tracing.sampling, &tracing.custom_tags, "tracing")?;
+    for d in domains {
+        if let Some(dt) = &d.tracing {
+            let context = format!("domain {} tracing", d.domain);
+            check_tracing_overrides(&dt.sampling, &dt.custom_tags, &context)?;
+        }
+    }
+    Ok(())
+}
+
+fn check_tracing_overrides(
+    sampling: &TracingSamplingSpec,
+    tags: &[CustomTagSpec],
+    context: &str,
+) -> Result<()> {
+    for (field, value) in [
+        ("random_sampling", sampling.random_sampling),
+        ("client_sampling", sampling.client_sampling),
+        ("overall_sampling", sampling.overall_sampling),
+    ] {
+        if let Some(v) = value {
+            if !(0.0..=100.0).contains(&v) {
+                bail!("{} {} {} must be between 0 and 100", context, field, v);
+            }
+        }
+    }
+    for tag in tags {
+        if tag.tag.is_empty() {
+            bail!("{} custom_tags entry has an empty tag name", context);
+        }
+        if !tag.is_valid() {
+            bail!(
+                "{} custom tag {} must set exactly one of literal, request_header or environment",
+                context,
+                tag.tag
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Check an access log block and its extra sinks; `context` names it in
+/// error messages.
+fn validate_access_log(
+    log: &AccessLogSpec,
+    context: &str,
+    upstreams: &[UpstreamSpec],
+) -> Result<()> {
+    check_access_log_sink(log, context, upstreams)?;
+    for (i, sink) in log.sinks.iter().enumerate() {
+        let context = format!("{} sinks[{}]", context, i);
+        if !sink.sinks.is_empty() {
+            bail!("{} cannot have nested sinks", context);
+        }
+        check_access_log_sink(sink, &context, upstreams)?;
+    }
+    Ok(())
+}
+
+fn check_access_log_sink(
+    log: &AccessLogSpec,
+    context: &str,
+    upstreams: &[UpstreamSpec],
+) -> Result<()> {
+    if log.r#type.is_remote() {
+        let Some(name) = &log.upstream else {
+            bail!(
+                "{} type {} requires an upstream",
+                context,
+                log.r#type.as_str()
+            );
+        };
+        let Some(u) = upstreams.iter().find(|u| &u.name == name) else {
+            bail!("{} references unknown upstream {}", context, name);
+        };
+        if !u.uses_http2() {
+            bail!(
+                "{} type {} needs gRPC but upstream {} does not enable http2",
+                context,
+                log.r#type.as_str(),
+                name
+            );
+        }
+    } else if log.upstream.is_some() {
+        bail!(
+            "{} upstream is only used by the open_telemetry and grpc types",
+            context
+        );
+    }
+    if log.r#type == AccessLogType::Grpc && (log.format.is_some() || log.json_format.is_some()) {
+        bail!(
+            "{} type grpc sends structured entries; format and json_format are not supported",
+            context
+        );
+    }
+    if log.format.is_some() && log.json_format.is_some() {
+        bail!("{} sets both format and json_format", context);
+    }
+    if log.json_format.as_ref().is_some_and(|j| j.is_empty()) {
+        bail!("{} json_format must have at least one field", context);
+    }
+    if log.r#type == AccessLogType::File && log.path.is_empty() {
+        bail!("{} type file requires a path", context);
+    }
+    if let Some(filter) = &log.filter {
+        if filter.sample_percent.is_some_and(|p| p > 100) {
+            bail!(
+                "{} filter sample_percent must be between 0 and 100",
+                context
+            );
+        }
+        for range in &filter.status_codes {
+            if range.min.is_none() && range.max.is_none() {
+                bail!("{} status_codes range needs min or max", context);
+            }
+            for code in range.min.iter().chain(range.max.iter()) {
+                if !(100..=599).contains(code) {
+                    bail!("{} status code {} is out of range 100-599", context, code);
+                }
+            }
+            if let (Some(min), Some(max)) = (range.min, range.max) {
+                if min > max {
+                    bail!(
+                        "{} status_codes range {}-{} has min above max",
+                        context,
+                        min,
+                        max
+                    );
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Routes without their own timeout inherit defaults.route_timeout; below
+/// this a WebSocket is likely cut off mid-session.
+const WEBSOCKET_MIN_TIMEOUT_SECS: f64 = 3600.0;
+
+/// Largest Maglev lookup table Envoy accepts
+const MAGLEV_MAX_TABLE_SIZE: u64 = 5_000_011;
+
+/// Non-fatal findings about a valid configuration, printed as warnings.
+pub fn lint_model(loaded: &Loaded) -> Vec<String> {
+    let mut warnings = Vec::new();
+    if !loaded.admin.is_local_only() {
+        warnings.push(format!(
+            "admin binds to {}, exposing config dumps and runtime changes to the network; bind 127.0.0.1 and use readonly_listener for /ready and /stats/prometheus",
+            loaded.admin.address
+        ));
+    }
+    let inherited = &loaded.defaults.route_timeout;
+    let short = duration_secs(inherited)
+        .is_some_and(|secs| secs > 0.0 && secs < WEBSOCKET_MIN_TIMEOUT_SECS);
+
+    for d in &loaded.domains {
+        let hcm_websocket = d
+            .http_connection_manager
+            .as_ref()
+            .is_some_and(|h| h.websocket);
+        for r in &d.routes {
+            if (r.websocket || hcm_websocket) && r.timeout.is_none() && short {
+                warnings.push(format!(
+                    "domain {} websocket route {} inherits route_timeout {}, which closes long-lived connections; set timeout: 0s on the route to disable it",
+                    d.domain,
+                    route_label(r),
+                    inherited
+                ));
+            }
+            // Hash policies are silently ignored by non-hashing load balancers
+            if !r.hash_policy.is_empty() {
+                let upstream = r
+                    .to_upstream
+                    .as_ref()
+                    .and_then(|name| loaded.upstreams.iter().find(|u| &u.name == name));
+                if let Some(u) = upstream.filter(|u| !u.lb_policy.is_consistent_hash()) {
+                    warnings.push(format!(
+                        "domain {} route {} sets hash_policy but upstream {} uses lb_policy {}, which ignores it; use RING_HASH or MAGLEV",
+                        d.domain,
+                        route_label(r),
+                        u.name,
+                        u.lb_policy.as_str()
+                    ));
+                }
+            }
+            if r.m.grpc {
+                let upstream = r
+                    .to_upstream
+                    .as_ref()
+                    .and_then(|name| loaded.upstreams.iter().find(|u| &u.name == name));
+                if let Some(u) = upstream.filter(|u| !u.uses_http2()) {
+                    warnings.push(format!(
+                        "domain {} gRPC route {} targets upstream {} without http2: true; gRPC needs HTTP/2 (set http2: true or preset: grpc)",
+                        d.domain,
+                        route_label(r),
+                        u.name
+                    ));
+                }
+            }
+        }
+    }
+    warnings
+}
+
+fn route_label(r: &RouteSpec) -> String {
+    match (&r.m.prefix, &r.m.path) {
+        (Some(prefix), _) => format!("prefix {}", prefix),
+        (None, Some(path)) => format!("path {}", path),
+        (None, None) => "(no path match)".to_string(),
+    }
+}
+
+/// Seconds in an Envoy-style duration such as "500ms", "30s", "5m" or "1h".
+fn duration_secs(d: &str) -> Option<f64> {
+    let (value, scale) = if let Some(v) = d.strip_suffix("ms") {
+        (v, 0.001)
+    } else if let Some(v) = d.strip_suffix('s') {
+        (v, 1.0)
+    } else if let Some(v) = d.strip_suffix('m') {
+        (v, 60.0)
+    } else if let Some(v) = d.strip_suffix('h') {
+        (v, 3600.0)
+    } else {
+        return None;
+    };
+    value.parse::<f64>().ok().map(|v| v * scale)
+}
+
+fn check_listener_limits(limits: &ListenerLimitsSpec, context: &str) -> Result<()> {
+    if let Some(rl) = &limits.connection_rate_limit {
+        if rl.max_tokens == 0 || rl.tokens_per_fill == 0 {
+            bail!(
+                "{} connection_rate_limit max_tokens and tokens_per_fill must be at least 1",
+                context
+            );
+        }
+        match duration_secs(&rl.fill_interval) {
+            Some(secs) if secs >= 0.05 => {}
+            Some(_) => bail!(
+                "{} connection_rate_limit fill_interval {} must be at least 50ms",
+                context,
+                rl.fill_interval
+            ),
+            None => bail!(
+                "{} connection_rate_limit fill_interval {} is not a valid duration",
+                context,
+                rl.fill_interval
+            ),
+        }
+    }
+    if limits.max_connections == Some(0) {
+        bail!("{} max_connections must be at least 1", context);
+    }
+    if limits.per_connection_buffer_limit_bytes == Some(0) {
+        bail!(
+            "{} per_connection_buffer_limit_bytes must be at least 1",
+            context
+        );
+    }
+    Ok(())
+}
+
+fn check_jwt_provider(
+    domain: &str,
+    jwt: &JwtRequirementSpec,
+    policies: &PoliciesSpec,
+) -> Result<()> {
+    let Some(provider) = &jwt.provider else {
+        bail!("domain {} jwt requirement needs a provider", domain);
+    };
+    if !policies.jwt_providers.contains_key(provider) {
+        bail!(
+            "domain {} references unknown jwt provider {}",
+            domain,
+            provider
+        );
+    }
+    Ok(())
+}
+
+fn is_valid_cidr(cidr: &str) -> bool {
+    let (addr, len) = match cidr.split_once('/') {
+        Some((addr, len)) => (addr, Some(len)),
+        None => (cidr, None),
+    };
+    let max = match addr.parse::<IpAddr>() {
+        Ok(IpAddr::V4(_)) => 32,
+        Ok(IpAddr::V6(_)) => 128,
+        Err(_) => return false,
+    };
+    len.map_or(true, |len| len.parse::<u32>().is_ok_and(|l| l <= max))
+}
+
+fn is_prime(n: u64) -> bool {
+    n >= 2 && (2..).take_while(|i| i * i <= n).all(|i| n % i != 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::*;
+    use indexmap::IndexMap;
+
+    #[test]
+    fn test_validate_model_success() {
+        let domains = vec![DomainSpec {
+            domain: "example.com".to_string(),
+            mode: "terminate_https_443".to_string(),
+            tls: Some(TlsSpec {
+                cert_chain: Some("/path/to/cert".to_string()),
+                private_key: Some("/path/to/key".to_string()),
+                ..Default::default()
+            }),
+            routes: vec![RouteSpec {
+                m: MatchSpec {
+                    prefix: Some("/api".to_string()),
+                    path: None,
+                    headers: vec![],
+                    ..Default::default()
+                },
+                to_upstream: Some("api_backend".to_string()),
+                timeout: Some("30s".to_string()),
+                per_filter_config: None,
+                prefix_rewrite: None,
+                direct_response: None,
+                ..Default::default()
+            }],
+            http_connection_manager: None,
+            normalize_path: None,
+            merge_slashes: None,
+            aws_signing: None,
+            ..Default::default()
+        }];
+
+        let upstreams = vec![
+            UpstreamSpec {
+                name: "api_backend".to_string(),
+                connect_timeout: "5s".to_string(),
+                r#type: ClusterType::StrictDns,
+                lb_policy: LbPolicy::RoundRobin,
+                http2: false,
+                endpoints: vec![Endpoint {
+                    address: "127.0.0.1".to_string(),
+                    port: 8080,
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+            UpstreamSpec {
+                name: "cilium_http".to_string(), // default http upstream
+                connect_timeout: "5s".to_string(),
+                r#type: ClusterType::StrictDns,
+                lb_policy: LbPolicy::RoundRobin,
+                http2: false,
+                endpoints: vec![Endpoint {
+                    address: "127.0.0.1".to_string(),
+                    port: 80,
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+            UpstreamSpec {
+                name: "cilium_tls".to_string(), // default tls upstream
+                connect_timeout: "5s".to_string(),
+                r#type: ClusterType::StrictDns,
+                lb_policy: LbPolicy::RoundRobin,
+                http2: false,
+                endpoints: vec![Endpoint {
+                    address: "127.0.0.1".to_string(),
+                    port: 443,
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+        ];
+
+        let policies = PoliciesSpec {
+            local_ratelimits: Default::default(),
+            ..Default::default()
+        };
+
+        let defaults = DefaultsSpec {
+            route_timeout: "60s".to_string(),
+            http_default_upstream: "cilium_http".to_string(),
+            tls_passthrough_upstream: "cilium_tls".to_string(),
+            ..Default::default()
+        };
+
+        let result = validate_model(&domains, &upstreams, &policies, &defaults, &[]);
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_validate_model_duplicate_domain() {
+        let domains = vec![
+            DomainSpec {
+                domain: "example.com".to_string(),
+                mode: "terminate_https_443".to_string(),
+                tls: Some(TlsSpec {
+                    cert_chain: Some("/path/to/cert".to_string()),
+                    private_key: Some("/path/to/key".to_string()),
+                    ..Default::default()
+                }),
+                routes: vec![],
+                http_connection_manager: None,
+                normalize_path: None,
+                merge_slashes: None,
+                aws_signing: None,
+                ..Default::default()
+            },
+            DomainSpec {
+                domain: "example.com".to_string(), // duplicate
+                mode: "terminate_https_443".to_string(),
+                tls: Some(TlsSpec {
+                    cert_chain: Some("/path/to/cert2".to_string()),
+                    private_key: Some("/path/to/key2".to_string()),
+                    ..Default::default()
+                }),
+                routes: vec![],
+                http_connection_manager: None,
+                normalize_path: None,
+                merge_slashes: None,
+                aws_signing: None,
+                ..Default::default()
+            },
+        ];
+
+        let upstreams = vec![
+            UpstreamSpec {
+                name: "cilium_http".to_string(),
+                connect_timeout: "5s".to_string(),
+                r#type: ClusterType::StrictDns,
+                lb_policy: LbPolicy::RoundRobin,
+                http2: false,
+                endpoints: vec![Endpoint {
+                    address: "127.0.0.1".to_string(),
+                    port: 80,
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+            UpstreamSpec {
+                name: "cilium_tls".to_string(),
+                connect_timeout: "5s".to_string(),
+                r#type: ClusterType::StrictDns,
+                lb_policy: LbPolicy::RoundRobin,
+                http2: false,
+                endpoints: vec![Endpoint {
+                    address: "127.0.0.1".to_string(),
+                    port: 443,
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+        ];
+
+        let policies = PoliciesSpec {
+            local_ratelimits: Default::default(),
+            ..Default::default()
+        };
+
+        let defaults = DefaultsSpec {
+            route_timeout: "60s".to_string(),
+            http_default_upstream: "cilium_http".to_string(),
+            tls_passthrough_upstream: "cilium_tls".to_string(),
+            ..Default::default()
+        };
+
+        let result = validate_model(&domains, &upstreams, &policies, &defaults, &[]);
+        assert!(result.is_err());
+        assert!(result.unwrap_err().to_string().contains("duplicate domain"));
+    }
+
+    #[test]
+    fn test_validate_model_terminate_without_tls() {
+        let domains = vec![DomainSpec {
+            domain: "example.com".to_string(),
+            mode: "terminate_https_443".to_string(), // requires TLS
+            tls: None,                               // but no TLS provided
+            routes: vec![],
+            http_connection_manager: None,
+            normalize_path: None,
+            merge_slashes: None,
+            aws_signing: None,
+            ..Default::default()
+        }];
+
+        let upstreams = vec![
+            UpstreamSpec {
+                name: "cilium_http".to_string(),
+                connect_timeout: "5s".to_string(),
+                r#type: ClusterType::StrictDns,
+                lb_policy: LbPolicy::RoundRobin,
+                http2: false,
+                endpoints: vec![Endpoint {
+                    address: "127.0.0.1".to_string(),
+                    port: 80,
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+            UpstreamSpec {
+                name: "cilium_tls".to_string(),
+                connect_timeout: "5s".to_string(),
+                r#type: ClusterType::StrictDns,
+                lb_policy: LbPolicy::RoundRobin,
+                http2: false,
+                endpoints: vec![Endpoint {
+                    address: "127.0.0.1".to_string(),
+                    port: 443,
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+        ];
+
+        let policies = PoliciesSpec {
+            local_ratelimits: Default::default(),
+            ..Default::default()
+        };
+
+        let defaults = DefaultsSpec {
+            route_timeout: "60s".to_string(),
+            http_default_upstream: "cilium_http".to_string(),
+            tls_passthrough_upstream: "cilium_tls".to_string(),
+            ..Default::default()
+        };
+
+        let result = validate_model(&domains, &upstreams, &policies, &defaults, &[]);
+        assert!(result.is_err());
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("requires tls block"));
+    }
+
+    #[test]
+    fn test_validate_model_unsupported_mode() {
+        let domains = vec![DomainSpec {
+            domain: "example.com".to_string(),
+            mode: "unsupported_mode".to_string(), // not supported
+            tls: None,
+            routes: vec![],
+            http_connection_manager: None,
+            normalize_path: None,
+            merge_slashes: None,
+            aws_signing: None,
+            ..Default::default()
+        }];
+
+        let upstreams = vec![
+            UpstreamSpec {
+                name: "cilium_http".to_string(),
+                connect_timeout: "5s".to_string(),
+                r#type: ClusterType::StrictDns,
+                lb_policy: LbPolicy::RoundRobin,
+                http2: false,
+                endpoints: vec![Endpoint {
+                    address: "127.0.0.1".to_string(),
+                    port: 80,
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+            UpstreamSpec {
+                name: "cilium_tls".to_string(),
+                connect_timeout: "5s".to_string(),
+                r#type: ClusterType::StrictDns,
+                lb_policy: LbPolicy::RoundRobin,
+                http2: false,
+                endpoints: vec![Endpoint {
+                    address: "127.0.0.1".to_string(),
+                    port: 443,
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+        ];
+
+        let policies = PoliciesSpec {
+            local_ratelimits: Default::default(),
+            ..Default::default()
+        };
+
+        let defaults = DefaultsSpec {
+            route_timeout: "60s".to_string(),
+            http_default_upstream: "cilium_http".to_string(),
+            tls_passthrough_upstream: "cilium_tls".to_string(),
+            ..Default::default()
+        };
+
+        let result = validate_model(&domains, &upstreams, &policies, &defaults, &[]);
+        assert!(result.is_err());
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("has unsupported mode"));
+    }
+
+    #[test]
+    fn test_validate_model_missing_default_upstream() {
+        let domains = vec![];
+
+        let upstreams = vec![UpstreamSpec {
+            name: "some_upstream".to_string(),
+            connect_timeout: "5s".to_string(),
+            r#type: ClusterType::StrictDns,
+            lb_policy: LbPolicy::RoundRobin,
+            http2: false,
+            endpoints: vec![Endpoint {
+                address: "127.0.0.1".to_string(),
+                port: 80,
+                ..Default::default()
+            }],
+            ..Default::default()
+        }];
+
+        let policies = PoliciesSpec {
+            local_ratelimits: Default::default(),
+            ..Default::default()
+        };
+
+        let defaults = DefaultsSpec {
+            route_timeout: "60s".to_string(),
+            http_default_upstream: "missing_upstream".to_string(), // doesn't exist
+            tls_passthrough_upstream: "cilium_tls".to_string(),    // also missing
+            ..Default::default()
+        };
+
+        let result = validate_model(&domains, &upstreams, &policies, &defaults, &[]);
+        assert!(result.is_err());
+        let error_msg = result.unwrap_err().to_string();
+        assert!(
+            error_msg.contains("http_default_upstream")
+                || error_msg.contains("tls_passthrough_upstream")
+        );
+    }
+
+    #[test]
+    fn test_validate_model_upstream_with_no_endpoints() {
+        let domains = vec![];
+
+        let upstreams = vec![
+            UpstreamSpec {
+                name: "empty_upstream".to_string(),
+                connect_timeout: "5s".to_string(),
+                r#type: ClusterType::StrictDns,
+                lb_policy: LbPolicy::RoundRobin,
+                http2: false,
+                endpoints: vec![], // no endpoints
+                ..Default::default()
+            },
+            UpstreamSpec {
+                name: "cilium_http".to_string(),
+                connect_timeout: "5s".to_string(),
+                r#type: ClusterType::StrictDns,
+                lb_policy: LbPolicy::RoundRobin,
+                http2: false,
+                endpoints: vec![Endpoint {
+                    address: "127.0.0.1".to_string(),
+                    port: 80,
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+            UpstreamSpec {
+                name: "cilium_tls".to_string(),
+                connect_timeout: "5s".to_string(),
+                r#type: ClusterType::StrictDns,
+                lb_policy: LbPolicy::RoundRobin,
+                http2: false,
+                endpoints: vec![Endpoint {
+                    address: "127.0.0.1".to_string(),
+                    port: 443,
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+        ];
+
+        let policies = PoliciesSpec {
+            local_ratelimits: Default::default(),
+            ..Default::default()
+        };
+
+        let defaults = DefaultsSpec {
+            route_timeout: "60s".to_string(),
+            http_default_upstream: "cilium_http".to_string(),
+            tls_passthrough_upstream: "cilium_tls".to_string(),
+            ..Default::default()
+        };
+
+        let result = validate_model(&domains, &upstreams, &policies, &defaults, &[]);
+        assert!(result.is_err());
+        assert!(result.unwrap_err().to_string().contains("has no endpoints"));
+    }
+
+    #[test]
+    fn test_validate_model_route_with_unknown_upstream() {
+        let domains = vec![DomainSpec {
+            domain: "example.com".to_string(),
+            mode: "terminate_https_443".to_string(),
+            tls: Some(TlsSpec {
+                cert_chain: Some("/path/to/cert".to_string()),
+                private_key: Some("/path/to/key".to_string()),
+                ..Default::default()
+            }),
+            routes: vec![RouteSpec {
+                m: MatchSpec {
+                    prefix: Some("/api".to_string()),
+                    path: None,
+                    headers: vec![],
+                    ..Default::default()
+                },
+                to_upstream: Some("unknown_backend".to_string()), // doesn't exist
+                timeout: Some("30s".to_string()),
+                per_filter_config: None,
+                prefix_rewrite: None,
+                direct_response: None,
+                ..Default::default()
+            }],
+            http_connection_manager: None,
+            normalize_path: None,
+            merge_slashes: None,
+            aws_signing: None,
+            ..Default::default()
+        }];
+
+        let upstreams = vec![
+            UpstreamSpec {
+                name: "cilium_http".to_string(),
+                connect_timeout: "5s".to_string(),
+                r#type: ClusterType::StrictDns,
+                lb_policy: LbPolicy::RoundRobin,
+                http2: false,
+                endpoints: vec![Endpoint {
+                    address: "127.0.0.1".to_string(),
+                    port: 80,
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+            UpstreamSpec {
+                name: "cilium_tls".to_string(),
+                connect_timeout: "5s".to_string(),
+                r#type: ClusterType::StrictDns,
+                lb_policy: LbPolicy::RoundRobin,
+                http2: false,
+                endpoints: vec![Endpoint {
+                    address: "127.0.0.1".to_string(),
+                    port: 443,
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+        ];
+
+        let policies = PoliciesSpec {
+            local_ratelimits: Default::default(),
+            ..Default::default()
+        };
+
+        let defaults = DefaultsSpec {
+            route_timeout: "60s".to_string(),
+            http_default_upstream: "cilium_http".to_string(),
+            tls_passthrough_upstream: "cilium_tls".to_string(),
+            ..Default::default()
+        };
+
+        let result = validate_model(&domains, &upstreams, &policies, &defaults, &[]);
+        assert!(result.is_err());
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("route references unknown upstream"));
+    }
+
+    #[test]
+    fn test_validate_model_domain_with_unknown_cors_policy() {
+        let domains = vec![DomainSpec {
+            domain: "example.com".to_string(),
+            mode: "terminate_https_443".to_string(),
+            tls: Some(TlsSpec {
+                cert_chain: Some("/path/to/cert".to_string()),
+                private_key: Some("/path/to/key".to_string()),
+                ..Default::default()
+            }),
+            routes: vec![RouteSpec {
+                m: MatchSpec {
+                    prefix: Some("/api".to_string()),
+                    ..Default::default()
+                },
+                to_upstream: Some("cilium_http".to_string()),
+                timeout: Some("30s".to_string()),
+                ..Default::default()
+            }],
+            cors: Some("spa".to_string()), // no cors policies defined
+            ..Default::default()
+        }];
+
+        let upstreams = vec![
+            upstream("cilium_http", "127.0.0.1", 80),
+            upstream("cilium_tls", "127.0.0.1", 443),
+        ];
+
+        let policies = PoliciesSpec::default();
+
+        let defaults = defaults("cilium_http", "cilium_tls");
+
+        let result = validate_model(&domains, &upstreams, &policies, &defaults, &[]);
+        assert!(result.is_err());
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("unknown cors policy spa"));
+    }
+
+    #[test]
+    fn test_validate_model_maglev_table_size() {
+        let mut upstreams = vec![
+            UpstreamSpec {
+                lb_policy: LbPolicy::Maglev,
+                maglev: Some(MaglevSpec {
+                    table_size: Some(65536),
+                }),
+                ..upstream("cilium_http", "127.0.0.1", 80)
+            },
+            upstream("cilium_tls", "127.0.0.1", 443),
+        ];
+        let policies = PoliciesSpec::default();
+        let defaults = defaults("cilium_http", "cilium_tls");
+
+        let result = validate_model(&[], &upstreams, &policies, &defaults, &[]);
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("must be a prime number, got 65536"));
+
+        // Rejected before the primality check, which would take far too long
+        upstreams[0].maglev = Some(MaglevSpec {
+            table_size: Some(u64::MAX),
+        });
+        let result = validate_model(&[], &upstreams, &policies, &defaults, &[]);
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            format!(
+                "upstream cilium_http maglev.table_size must be at most 5000011, got {}",
+                u64::MAX
+            )
+        );
+
+        upstreams[0].maglev = Some(MaglevSpec {
+            table_size: Some(5_000_011),
+        });
+        assert!(validate_model(&[], &upstreams, &policies, &defaults, &[]).is_ok());
+    }
+
+    #[test]
+    fn test_lint_model_hash_policy_without_hashing_upstream() {
+        let loaded = Loaded {
+            domains: vec![DomainSpec {
+                domain: "example.com".to_string(),
+                mode: "terminate_https_443".to_string(),
+                tls: Some(TlsSpec {
+                    cert_chain: Some("/path/to/cert".to_string()),
+                    private_key: Some("/path/to/key".to_string()),
+                    ..Default::default()
+                }),
+                routes: vec![RouteSpec {
+                    m: MatchSpec {
+                        prefix: Some("/api".to_string()),
+                        ..Default::default()
+                    },
+                    to_upstream: Some("web".to_string()),
+                    hash_policy: vec![HashPolicySpec {
+                        header: None,
+                        cookie: Some(HashCookieSpec {
+                            name: "session".to_string(),
+                            ttl: Some("3600s".to_string()),
+                            path: None,
+                        }),
+                        source_ip: false,
+                        terminal: false,
+                    }],
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }],
+            upstreams: vec![upstream("web", "127.0.0.1", 80)], // ROUND_ROBIN
+            ..loaded()
+        };
+
+        // Envoy accepts the config and ignores the hash policy
+        assert!(validate_model(
+            &loaded.domains,
+            &loaded.upstreams,
+            &loaded.policies,
+            &loaded.defaults,
+            &[]
+        )
+        .is_ok());
+        assert_eq!(
+            lint_model(&loaded),
+            vec![
+                "domain example.com route prefix /api sets hash_policy but upstream web uses lb_policy ROUND_ROBIN, which ignores it; use RING_HASH or MAGLEV"
+                    .to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_model_route_with_unknown_rate_limit_policy() {
+        let domains = vec![DomainSpec {
+            domain: "example.com".to_string(),
+            mode: "terminate_https_443".to_string(),
+            tls: Some(TlsSpec {
+                cert_chain: Some("/path/to/cert".to_string()),
+                private_key: Some("/path/to/key".to_string()),
+                ..Default::default()
+            }),
+            routes: vec![RouteSpec {
+                m: MatchSpec {
+                    prefix: Some("/api".to_string()),
+                    path: None,
+                    headers: vec![],
+                    ..Default::default()
+                },
+                to_upstream: Some("api_backend".to_string()),
+                timeout: Some("30s".to_string()),
+                per_filter_config: Some(PerFilterConfigRef {
+                    local_ratelimit: Some("unknown_policy".to_string()), // doesn't exist
+                    ..Default::default()
+                }),
+                prefix_rewrite: None,
+                direct_response: None,
+                ..Default::default()
+            }],
+            http_connection_manager: None,
+            normalize_path: None,
+            merge_slashes: None,
+            aws_signing: None,
+            ..Default::default()
+        }];
+
+        let upstreams = vec![
+            UpstreamSpec {
+                name: "api_backend".to_string(),
+                connect_timeout: "5s".to_string(),
+                r#type: ClusterType::StrictDns,
+                lb_policy: LbPolicy::RoundRobin,
+                http2: false,
+                endpoints: vec![Endpoint {
+                    address: "127.0.0.1".to_string(),
+                    port: 8080,
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+            UpstreamSpec {
+                name: "cilium_http".to_string(),
+                connect_timeout: "5s".to_string(),
+                r#type: ClusterType::StrictDns,
+                lb_policy: LbPolicy::RoundRobin,
+                http2: false,
+                endpoints: vec![Endpoint {
+                    address: "127.0.0.1".to_string(),
+                    port: 80,
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+            UpstreamSpec {
+                name: "cilium_tls".to_string(),
+                connect_timeout: "5s".to_string(),
+                r#type: ClusterType::StrictDns,
+                lb_policy: LbPolicy::RoundRobin,
+                http2: false,
+                endpoints: vec![Endpoint {
+                    address: "127.0.0.1".to_string(),
+                    port: 443,
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+        ];
+
+        let policies = PoliciesSpec {
+            local_ratelimits: Default::default(), // empty - no policies
+            ..Default::default()
+        };
+
+        let defaults = DefaultsSpec {
+            route_timeout: "60s".to_string(),
+            http_default_upstream: "cilium_http".to_string(),
+            tls_passthrough_upstream: "cilium_tls".to_string(),
+            ..Default::default()
+        };
+
+        let result = validate_model(&domains, &upstreams, &policies, &defaults, &[]);
+        assert!(result.is_err());
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("route references unknown local_ratelimit policy"));
+    }
+
+    #[test]
+    fn test_validate_model_invalid_max_ejection_percent() {
+        let upstreams = vec![
+            UpstreamSpec {
+                outlier_detection: Some(OutlierDetectionSpec {
+                    consecutive_5xx: Some(5),
+                    consecutive_gateway_failure: None,
+                    interval: None,
+                    base_ejection_time: None,
+                    max_ejection_percent: Some(150),
+                }),
+                ..upstream("cilium_http", "127.0.0.1", 80)
+            },
+            upstream("cilium_tls", "127.0.0.1", 443),
+        ];
+
+        let policies = PoliciesSpec::default();
+
+        let defaults = defaults("cilium_http", "cilium_tls");
+
+        let result = validate_model(&[], &upstreams, &policies, &defaults, &[]);
+        assert!(result.is_err());
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("max_ejection_percent"));
+    }
+
+    #[test]
+    fn test_validate_model_grpc_ext_authz_without_http2() {
+        let upstreams = vec![
+            upstream("cilium_http", "127.0.0.1", 80),
+            upstream("cilium_tls", "127.0.0.1", 443),
+        ];
+
+        let mut ext_authz = IndexMap::new();
+        ext_authz.insert(
+            "opa".to_string(),
+            ExtAuthzSpec {
+                mode: ExtAuthzMode::Grpc,
+                upstream: "cilium_http".to_string(), // http2: false
+                timeout: "1s".to_string(),
+                failure_mode_allow: false,
+                path_prefix: None,
+                allowed_request_headers: vec![],
+                allowed_upstream_headers: vec![],
+            },
+        );
+        let policies = PoliciesSpec {
+            ext_authz,
+            ..Default::default()
+        };
+
+        let defaults = defaults("cilium_http", "cilium_tls");
+
+        let result = validate_model(&[], &upstreams, &policies, &defaults, &[]);
+        assert!(result.is_err());
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("does not enable http2"));
+    }
+
+    #[test]
+    fn test_validate_model_global_ratelimit_with_invalid_action() {
+        let upstreams = vec![
+            UpstreamSpec {
+                http2: true,
+                ..upstream("cilium_http", "127.0.0.1", 80)
+            },
+            upstream("cilium_tls", "127.0.0.1", 443),
+        ];
+
+        let mut global_ratelimits = IndexMap::new();
+        global_ratelimits.insert(
+            "per_client".to_string(),
+            GlobalRateLimitSpec {
+                actions: vec![RateLimitActionSpec {
+                    remote_address: true,
+                    request_headers: None,
+                    generic_key: Some(GenericKeyActionSpec {
+                        descriptor_value: "api".to_string(),
+                        descriptor_key: None,
+                    }),
+                }],
+            },
+        );
+        let policies = PoliciesSpec {
+            global_ratelimits,
+            ratelimit_service: Some(RateLimitServiceSpec {
+                upstream: "cilium_http".to_string(),
+                domain: "edge".to_string(),
+                timeout: "20ms".to_string(),
+                failure_mode_deny: false,
+            }),
+            ..Default::default()
+        };
 
-        let result = validate_model(&domains, &upstreams, &policies, &defaults);
-        assert!(result.is_ok());
+        let defaults = defaults("cilium_http", "cilium_tls");
+
+        let result = validate_model(&[], &upstreams, &policies, &defaults, &[]);
+        assert!(result.is_err());
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("global_ratelimit per_client actions must each set exactly one"));
     }
 
     #[test]
-    fn test_validate_model_duplicate_domain() {
-        let domains = vec![
-            DomainSpec {
-                domain: "example.com".to_string(),
-                mode: "terminate_https_443".to_string(),
-                tls: Some(TlsSpec {
-                    cert_chain: "/path/to/cert".to_string(),
-                    private_key: "/path/to/key".to_string(),
-                }),
-                routes: vec![],
-                http_connection_manager: None,
-                normalize_path: None,
-                merge_slashes: None,
-                aws_signing: None,
-            },
-            DomainSpec {
-                domain: "example.com".to_string(), // duplicate
-                mode: "terminate_https_443".to_string(),
-                tls: Some(TlsSpec {
-                    cert_chain: "/path/to/cert2".to_string(),
-                    private_key: "/path/to/key2".to_string(),
-                }),
-                routes: vec![],
-                http_connection_manager: None,
-                normalize_path: None,
-                merge_slashes: None,
-                aws_signing: None,
+    fn test_validate_model_local_ratelimit_with_invalid_descriptor() {
+        let upstreams = vec![
+            UpstreamSpec {
+                http2: true,
+                ..upstream("cilium_http", "127.0.0.1", 80)
             },
+            upstream("cilium_tls", "127.0.0.1", 443),
         ];
 
-        let upstreams = vec![
-            UpstreamSpec {
-                name: "cilium_http".to_string(),
-                connect_timeout: "5s".to_string(),
-                r#type: "STRICT_DNS".to_string(),
-                lb_policy: "ROUND_ROBIN".to_string(),
-                http2: false,
-                endpoints: vec![Endpoint {
-                    address: "127.0.0.1".to_string(),
-                    port: 80,
+        let mut local_ratelimits = IndexMap::new();
+        local_ratelimits.insert(
+            "api".to_string(),
+            TokenBucket {
+                max_tokens: 100,
+                tokens_per_fill: 100,
+                fill_interval: "1s".to_string(),
+                descriptors: vec![LocalRateLimitDescriptorSpec {
+                    header: None,
+                    remote_address: true,
+                    value: "office".to_string(), // not an IP
+                    max_tokens: 10,
+                    tokens_per_fill: 10,
+                    fill_interval: "1s".to_string(),
                 }],
+                ..Default::default()
             },
-            UpstreamSpec {
-                name: "cilium_tls".to_string(),
-                connect_timeout: "5s".to_string(),
-                r#type: "STRICT_DNS".to_string(),
-                lb_policy: "ROUND_ROBIN".to_string(),
-                http2: false,
-                endpoints: vec![Endpoint {
-                    address: "127.0.0.1".to_string(),
-                    port: 443,
-                }],
+        );
+        let policies = PoliciesSpec {
+            local_ratelimits,
+            ..Default::default()
+        };
+
+        let defaults = defaults("cilium_http", "cilium_tls");
+
+        let result = validate_model(&[], &upstreams, &policies, &defaults, &[]);
+        assert!(result.is_err());
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("remote_address descriptor value office is not an IP address"));
+    }
+
+    #[test]
+    fn test_validate_model_access_policy_with_invalid_cidr() {
+        let upstreams = vec![
+            upstream("cilium_http", "127.0.0.1", 80),
+            upstream("cilium_tls", "127.0.0.1", 443),
+        ];
+
+        let mut access = IndexMap::new();
+        access.insert(
+            "office_vpn".to_string(),
+            AccessPolicySpec {
+                action: RbacAction::Allow,
+                source_cidrs: vec!["10.8.0.0/33".to_string()], // prefix too long
+                except_cidrs: vec![],
+                headers: vec![],
+                enforce_l4: false,
             },
+        );
+        let policies = PoliciesSpec {
+            access,
+            ..Default::default()
+        };
+
+        let defaults = defaults("cilium_http", "cilium_tls");
+
+        let result = validate_model(&[], &upstreams, &policies, &defaults, &[]);
+        assert!(result.is_err());
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("invalid CIDR 10.8.0.0/33"));
+    }
+
+    #[test]
+    fn test_is_valid_cidr_rejects_empty_prefix_length() {
+        assert!(is_valid_cidr("10.0.0.0/8"));
+        assert!(is_valid_cidr("10.0.0.1"));
+        assert!(is_valid_cidr("::1/128"));
+        assert!(!is_valid_cidr("10.0.0.0/"));
+        assert!(!is_valid_cidr("::1/"));
+    }
+
+    #[test]
+    fn test_validate_model_compression_level_out_of_range() {
+        let upstreams = vec![
+            upstream("cilium_http", "127.0.0.1", 80),
+            upstream("cilium_tls", "127.0.0.1", 443),
         ];
 
+        let mut compression = IndexMap::new();
+        compression.insert(
+            "gzip_max".to_string(),
+            CompressionPolicySpec {
+                algorithm: CompressionAlgorithm::Gzip,
+                min_content_length: None,
+                content_types: vec![],
+                level: Some(11), // brotli range, gzip stops at 9
+            },
+        );
         let policies = PoliciesSpec {
-            local_ratelimits: Default::default(),
+            compression,
+            ..Default::default()
         };
 
+        let defaults = defaults("cilium_http", "cilium_tls");
+
+        let result = validate_model(&[], &upstreams, &policies, &defaults, &[]);
+        assert!(result.is_err());
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("level 11 is out of range for gzip"));
+    }
+
+    #[test]
+    fn test_validate_model_zero_max_connections() {
+        let upstreams = vec![
+            upstream("cilium_http", "127.0.0.1", 80),
+            upstream("cilium_tls", "127.0.0.1", 443),
+        ];
+
+        let policies = PoliciesSpec::default();
+
         let defaults = DefaultsSpec {
-            route_timeout: "60s".to_string(),
-            http_default_upstream: "cilium_http".to_string(),
-            tls_passthrough_upstream: "cilium_tls".to_string(),
+            listener_limits: ListenerLimitsSpec {
+                connection_rate_limit: None,
+                max_connections: Some(0),
+                per_connection_buffer_limit_bytes: None,
+            },
+            ..defaults("cilium_http", "cilium_tls")
         };
 
-        let result = validate_model(&domains, &upstreams, &policies, &defaults);
+        let result = validate_model(&[], &upstreams, &policies, &defaults, &[]);
         assert!(result.is_err());
-        assert!(result.unwrap_err().to_string().contains("duplicate domain"));
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("defaults.listener_limits max_connections must be at least 1"));
+    }
+
+    #[test]
+    fn test_validate_model_connection_rate_limit() {
+        let upstreams = vec![
+            upstream("cilium_http", "127.0.0.1", 80),
+            upstream("cilium_tls", "127.0.0.1", 443),
+        ];
+        let policies = PoliciesSpec::default();
+        let check = |max_tokens: u32, tokens_per_fill: u32, fill_interval: &str| {
+            let defaults = DefaultsSpec {
+                listener_limits: ListenerLimitsSpec {
+                    connection_rate_limit: Some(ConnectionRateLimitSpec {
+                        max_tokens,
+                        tokens_per_fill,
+                        fill_interval: fill_interval.to_string(),
+                    }),
+                    ..Default::default()
+                },
+                ..defaults("cilium_http", "cilium_tls")
+            };
+            validate_model(&[], &upstreams, &policies, &defaults, &[]).map_err(|e| e.to_string())
+        };
+
+        assert!(check(500, 100, "1s").is_ok());
+        assert!(check(0, 100, "1s")
+            .unwrap_err()
+            .contains("max_tokens and tokens_per_fill must be at least 1"));
+        assert!(check(500, 0, "1s")
+            .unwrap_err()
+            .contains("max_tokens and tokens_per_fill must be at least 1"));
+        assert!(check(500, 100, "often")
+            .unwrap_err()
+            .contains("fill_interval often is not a valid duration"));
+        assert!(check(500, 100, "10ms")
+            .unwrap_err()
+            .contains("fill_interval 10ms must be at least 50ms"));
+    }
+
+    #[test]
+    fn test_lint_model_websocket_route_with_inherited_timeout() {
+        let route = |prefix: &str, timeout: Option<&str>, websocket: bool| RouteSpec {
+            m: MatchSpec {
+                prefix: Some(prefix.to_string()),
+                ..Default::default()
+            },
+            to_upstream: Some("chat".to_string()),
+            timeout: timeout.map(str::to_string),
+            websocket,
+            ..Default::default()
+        };
+        let mut loaded = Loaded {
+            defaults: defaults("chat", "chat"),
+            domains: vec![DomainSpec {
+                domain: "chat.example.com".to_string(),
+                mode: "terminate_https_443".to_string(),
+                routes: vec![
+                    route("/ws/", None, true),
+                    route("/live/", Some("0s"), true),
+                    route("/", None, false),
+                ],
+                ..Default::default()
+            }],
+            ..loaded()
+        };
+
+        let warnings = lint_model(&loaded);
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains(
+            "domain chat.example.com websocket route prefix /ws/ inherits route_timeout 60s"
+        ));
+
+        loaded.defaults.route_timeout = "0s".to_string();
+        assert!(lint_model(&loaded).is_empty());
+
+        assert_eq!(duration_secs("1500ms"), Some(1.5));
+        assert_eq!(duration_secs("5m"), Some(300.0));
+        assert_eq!(duration_secs("soon"), None);
+    }
+
+    #[test]
+    fn test_lint_model_grpc_route_without_http2() {
+        let upstream = |name: &str, http2: bool, preset: Option<UpstreamPreset>| UpstreamSpec {
+            name: name.to_string(),
+            connect_timeout: "5s".to_string(),
+            r#type: ClusterType::StrictDns,
+            lb_policy: LbPolicy::RoundRobin,
+            http2,
+            preset,
+            endpoints: vec![Endpoint {
+                address: "127.0.0.1".to_string(),
+                port: 50051,
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let route = |prefix: &str, to: &str, grpc: bool| RouteSpec {
+            m: MatchSpec {
+                prefix: Some(prefix.to_string()),
+                grpc,
+                ..Default::default()
+            },
+            to_upstream: Some(to.to_string()),
+            ..Default::default()
+        };
+        let loaded = Loaded {
+            domains: vec![DomainSpec {
+                domain: "api.example.com".to_string(),
+                mode: "terminate_https_443".to_string(),
+                routes: vec![
+                    route("/orders.v1.", "orders", true),
+                    route("/users.v1.", "users", true),
+                    route("/", "web", false),
+                ],
+                ..Default::default()
+            }],
+            upstreams: vec![
+                upstream("orders", false, None),
+                upstream("users", false, Some(UpstreamPreset::Grpc)),
+                upstream("web", false, None),
+            ],
+            ..loaded()
+        };
+
+        let warnings = lint_model(&loaded);
+        assert_eq!(
+            warnings,
+            vec!["domain api.example.com gRPC route prefix /orders.v1. targets upstream orders without http2: true; gRPC needs HTTP/2 (set http2: true or preset: grpc)".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_validate_access_log() {
+        let mut json = IndexMap::new();
+        json.insert("status".to_string(), "%RESPONSE_CODE%".to_string());
+        let mut log = AccessLogSpec {
+            format: Some("%RESPONSE_CODE%".to_string()),
+            json_format: Some(json),
+            ..stdout_log()
+        };
+        let result = validate_access_log(&log, "access_log", &[]);
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("access_log sets both format and json_format"));
+
+        log.format = None;
+        log.filter = Some(AccessLogFilterSpec {
+            status_codes: vec![StatusCodeRangeSpec {
+                min: Some(500),
+                max: Some(400),
+            }],
+            sample_percent: None,
+            not_health_check: false,
+        });
+        let result = validate_access_log(&log, "domain example.com access_log", &[]);
+        assert!(result.unwrap_err().to_string().contains(
+            "domain example.com access_log status_codes range 500-400 has min above max"
+        ));
+
+        log.filter = None;
+        assert!(validate_access_log(&log, "access_log", &[]).is_ok());
+    }
+
+    #[test]
+    fn test_validate_tracing() {
+        let mut upstreams = vec![upstream("otel_collector", "otel-collector", 4317)];
+        let mut tracing = TracingSpec {
+            provider: TracingProvider::OpenTelemetry,
+            upstream: "otel_collector".to_string(),
+            service_name: "envoy".to_string(),
+            collector_endpoint: None,
+            sampling: TracingSamplingSpec::default(),
+            custom_tags: vec![],
+        };
+        let err = validate_tracing(&tracing, &[], &upstreams).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "tracing provider open_telemetry needs gRPC but upstream otel_collector does not enable http2"
+        );
+
+        upstreams[0].http2 = true;
+        assert!(validate_tracing(&tracing, &[], &upstreams).is_ok());
+
+        let domains: Vec<DomainSpec> = vec![serde_yaml::from_str(
+            r#"
+domain: api.example.com
+tracing:
+  sampling: { overall_sampling: 150 }
+"#,
+        )
+        .unwrap()];
+        let err = validate_tracing(&tracing, &domains, &upstreams).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "domain api.example.com tracing overall_sampling 150 must be between 0 and 100"
+        );
+
+        tracing.custom_tags.push(CustomTagSpec {
+            tag: "env".to_string(),
+            literal: Some("production".to_string()),
+            request_header: None,
+            environment: Some("ENV".to_string()),
+        });
+        let err = validate_tracing(&tracing, &[], &upstreams).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("tracing custom tag env must set exactly one of"));
+    }
+
+    #[test]
+    fn test_validate_stats_and_stat_prefix_collisions() {
+        let mut stats = StatsSpec {
+            sinks: vec![StatsSinkSpec {
+                r#type: StatsSinkType::Dogstatsd,
+                address: None,
+                port: None,
+                upstream: Some("statsd_exporter".to_string()),
+                prefix: None,
+            }],
+            tags: vec![],
+            use_all_default_tags: None,
+            include: vec![],
+            exclude: vec![],
+        };
+        let err = validate_stats(&stats, &[]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "stats sinks[0] (dogstatsd) only supports address, not upstream"
+        );
+
+        stats.sinks[0].upstream = None;
+        stats.sinks[0].address = Some("statsd.local".to_string());
+        stats.sinks[0].port = Some(8125);
+        let err = validate_stats(&stats, &[]).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("address statsd.local must be an IP address"));
+
+        stats.sinks[0].address = Some("127.0.0.1".to_string());
+        assert!(validate_stats(&stats, &[]).is_ok());
+
+        let domain = |yaml: &str| -> DomainSpec { serde_yaml::from_str(yaml).unwrap() };
+        let mut loaded = Loaded {
+            stats,
+            domains: vec![
+                domain("domain: api.example.com"),
+                domain(
+                    "{ domain: shop.example.com, http_connection_manager: { stat_prefix: edge } }",
+                ),
+            ],
+            ..loaded()
+        };
+        assert!(check_stat_prefixes(&loaded).is_ok());
+
+        loaded.domains.push(domain(
+            "{ domain: blog.example.com, http_connection_manager: { stat_prefix: edge } }",
+        ));
+        let err = check_stat_prefixes(&loaded).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "stat_prefix edge is used by both domain shop.example.com and domain blog.example.com"
+        );
+
+        loaded.domains.pop();
+        loaded.listeners = serde_yaml::from_str(
+            r#"
+internal_http_listeners:
+  - name: internal_api
+    address: 127.0.0.1
+    port: 8080
+    stat_prefix: api_example_com_https
+    domains: ["*"]
+    to_upstream: web
+"#,
+        )
+        .unwrap();
+        let err = check_stat_prefixes(&loaded).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "stat_prefix api_example_com_https is used by both domain api.example.com and listener internal_api"
+        );
+    }
+
+    #[test]
+    fn test_check_stat_prefixes_reserved_by_builtin_listeners() {
+        let domain = |yaml: &str| -> DomainSpec { serde_yaml::from_str(yaml).unwrap() };
+        let mut loaded = Loaded {
+            domains: vec![domain(
+                "{ domain: api.example.com, http_connection_manager: { stat_prefix: external_tls_passthrough } }",
+            )],
+            ..loaded()
+        };
+        let err = check_stat_prefixes(&loaded).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "stat_prefix external_tls_passthrough is used by both the HTTPS passthrough chain and domain api.example.com"
+        );
+
+        loaded.domains = vec![domain(
+            "{ domain: api.example.com, http_connection_manager: { stat_prefix: https_sni_listener_conn_rl } }",
+        )];
+        assert!(check_stat_prefixes(&loaded).is_ok());
+
+        loaded.defaults.listener_limits = ListenerLimitsSpec {
+            connection_rate_limit: Some(ConnectionRateLimitSpec {
+                max_tokens: 500,
+                tokens_per_fill: 100,
+                fill_interval: "1s".to_string(),
+            }),
+            ..Default::default()
+        };
+        let err = check_stat_prefixes(&loaded).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "stat_prefix https_sni_listener_conn_rl is used by both the HTTPS listener limits and domain api.example.com"
+        );
+
+        loaded.domains.clear();
+        loaded.listeners = serde_yaml::from_str(
+            r#"
+internal_http_listeners:
+  - name: internal_api
+    address: 127.0.0.1
+    port: 8080
+    stat_prefix: internal_api
+    domains: ["*"]
+    to_upstream: web
+    limits:
+      max_connections: 100
+  - name: metrics
+    address: 127.0.0.1
+    port: 8081
+    stat_prefix: internal_api_conn_limit
+    domains: ["*"]
+    to_upstream: web
+"#,
+        )
+        .unwrap();
+        let err = check_stat_prefixes(&loaded).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "stat_prefix internal_api_conn_limit is used by both listener internal_api limits and listener metrics"
+        );
+    }
+
+    #[test]
+    fn test_validate_admin_and_lint_exposed_address() {
+        let mut loaded = Loaded {
+            admin: AdminSpec {
+                address: "0.0.0.0".to_string(),
+                port: 9901,
+                readonly_listener: Some(AdminReadonlyListenerSpec {
+                    address: "0.0.0.0".to_string(),
+                    port: 9901,
+                }),
+                ..Default::default()
+            },
+            ..loaded()
+        };
+        let err = validate_admin(&loaded).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "admin readonly_listener port 9901 is already used by the admin interface"
+        );
+
+        let warnings = lint_model(&loaded);
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].starts_with("admin binds to 0.0.0.0, exposing config dumps"));
+
+        // A unix socket frees the TCP port and keeps admin off the network
+        loaded.admin.socket_path = Some("/var/run/envoy/admin.sock".to_string());
+        assert!(validate_admin(&loaded).is_ok());
+        assert!(lint_model(&loaded).is_empty());
+    }
+
+    #[test]
+    fn test_validate_builtin_listeners() {
+        let mut loaded = Loaded {
+            admin: AdminSpec {
+                address: "127.0.0.1".to_string(),
+                port: 9901,
+                readonly_listener: Some(AdminReadonlyListenerSpec {
+                    address: "0.0.0.0".to_string(),
+                    port: 8080,
+                }),
+                ..Default::default()
+            },
+            defaults: DefaultsSpec {
+                http_listener: BuiltinListenerSpec {
+                    enabled: true,
+                    address: "0.0.0.0".to_string(),
+                    port: Some(8443),
+                    ipv4_compat: true,
+                    additional_addresses: vec![],
+                },
+                https_listener: BuiltinListenerSpec {
+                    enabled: true,
+                    address: "::".to_string(),
+                    port: Some(8443),
+                    ipv4_compat: true,
+                    additional_addresses: vec![ListenerAddressSpec {
+                        address: "::".to_string(),
+                        port: None,
+                    }],
+                },
+                ..defaults("web", "web")
+            },
+            ..loaded()
+        };
+        assert_eq!(
+            validate_builtin_listeners(&loaded).unwrap_err().to_string(),
+            "defaults.http_listener ipv4_compat needs an IPv6 address such as ::, got 0.0.0.0"
+        );
+
+        loaded.defaults.http_listener.ipv4_compat = false;
+        assert_eq!(
+            validate_builtin_listeners(&loaded).unwrap_err().to_string(),
+            "defaults.https_listener binds address :: port 8443 more than once"
+        );
+
+        loaded.defaults.https_listener.additional_addresses[0].address = "10.0.0.5".to_string();
+        assert_eq!(
+            validate_builtin_listeners(&loaded).unwrap_err().to_string(),
+            "defaults.http_listener and defaults.https_listener both use port 8443"
+        );
+
+        // The readonly listener is checked against the configured ports, not 80/443
+        loaded.defaults.http_listener.port = Some(8080);
+        assert!(validate_builtin_listeners(&loaded).is_ok());
+        assert_eq!(
+            validate_admin(&loaded).unwrap_err().to_string(),
+            "admin readonly_listener port 8080 is already used by defaults.http_listener"
+        );
+
+        loaded.defaults.http_listener.enabled = false;
+        assert!(validate_admin(&loaded).is_ok());
+    }
+
+    #[test]
+    fn test_validate_overload() {
+        let mut overload = OverloadSpec {
+            refresh_interval: None,
+            max_heap_size_bytes: None,
+            max_active_downstream_connections: Some(50_000),
+            global_downstream_max_connections: Some(50_000),
+            actions: vec![OverloadActionSpec {
+                action: OverloadAction::StopAcceptingRequests,
+                heap_threshold: 95.0,
+            }],
+        };
+        assert!(validate_overload(&overload)
+            .unwrap_err()
+            .to_string()
+            .contains(
+                "sets both max_active_downstream_connections and global_downstream_max_connections"
+            ));
+
+        overload.global_downstream_max_connections = None;
+        assert_eq!(
+            validate_overload(&overload).unwrap_err().to_string(),
+            "overload actions trigger on heap usage and need max_heap_size_bytes"
+        );
+
+        overload.max_heap_size_bytes = Some(1_073_741_824);
+        assert_eq!(
+            validate_overload(&overload).unwrap_err().to_string(),
+            "overload action stop_accepting_requests heap_threshold 95 must be above 0 and at most 1"
+        );
+
+        overload.actions[0].heap_threshold = 0.95;
+        assert!(validate_overload(&overload).is_ok());
+    }
+
+    #[test]
+    fn test_validate_access_log_remote_sinks() {
+        let upstreams = vec![upstream("otel_collector", "otel-collector", 4317)];
+        let mut log = AccessLogSpec {
+            sinks: vec![AccessLogSpec {
+                r#type: AccessLogType::OpenTelemetry,
+                path: "/dev/stdout".to_string(),
+                upstream: Some("otel_collector".to_string()),
+                ..Default::default()
+            }],
+            ..stdout_log()
+        };
+        let result = validate_access_log(&log, "access_log", &upstreams);
+        assert!(result.unwrap_err().to_string().contains(
+            "access_log sinks[0] type open_telemetry needs gRPC but upstream otel_collector does not enable http2"
+        ));
+
+        log.sinks[0].upstream = Some("missing".to_string());
+        let result = validate_access_log(&log, "access_log", &upstreams);
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("access_log sinks[0] references unknown upstream missing"));
+
+        log.sinks[0].upstream = None;
+        log.sinks[0].r#type = AccessLogType::Grpc;
+        let result = validate_access_log(&log, "access_log", &upstreams);
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("access_log sinks[0] type grpc requires an upstream"));
+    }
+
+    #[test]
+    fn test_validate_model_jwt_provider_with_unknown_jwks_upstream() {
+        let upstreams = vec![
+            upstream("cilium_http", "127.0.0.1", 80),
+            upstream("cilium_tls", "127.0.0.1", 443),
+        ];
+
+        let mut jwt_providers = IndexMap::new();
+        jwt_providers.insert(
+            "auth0".to_string(),
+            JwtProviderSpec {
+                issuer: None,
+                audiences: vec![],
+                local_jwks: None,
+                remote_jwks: Some(RemoteJwksSpec {
+                    uri: "https://example.eu.auth0.com/.well-known/jwks.json".to_string(),
+                    upstream: "auth0_jwks".to_string(), // not defined
+                    timeout: "5s".to_string(),
+                    cache_duration: None,
+                }),
+                forward: false,
+                forward_payload_header: None,
+                claim_to_headers: vec![],
+            },
+        );
+        let policies = PoliciesSpec {
+            jwt_providers,
+            ..Default::default()
+        };
+
+        let defaults = defaults("cilium_http", "cilium_tls");
+
+        let result = validate_model(&[], &upstreams, &policies, &defaults, &[]);
+        assert!(result.is_err());
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("unknown upstream auth0_jwks"));
     }
 
     #[test]
-    fn test_validate_model_terminate_without_tls() {
-        let domains = vec![DomainSpec {
-            domain: "example.com".to_string(),
-            mode: "terminate_https_443".to_string(), // requires TLS
-            tls: None,                               // but no TLS provided
-            routes: vec![],
-            http_connection_manager: None,
-            normalize_path: None,
-            merge_slashes: None,
-            aws_signing: None,
-        }];
-
+    fn test_validate_model_static_upstream_with_hostname() {
         let upstreams = vec![
             UpstreamSpec {
                 name: "cilium_http".to_string(),
                 connect_timeout: "5s".to_string(),
-                r#type: "STRICT_DNS".to_string(),
-                lb_policy: "ROUND_ROBIN".to_string(),
-                http2: false,
+                r#type: ClusterType::Static,
+                lb_policy: LbPolicy::RoundRobin,
                 endpoints: vec![Endpoint {
-                    address: "127.0.0.1".to_string(),
+                    address: "backend.internal".to_string(), // not an IP
                     port: 80,
+                    ..Default::default()
                 }],
+                ..Default::default()
             },
-            UpstreamSpec {
-                name: "cilium_tls".to_string(),
-                connect_timeout: "5s".to_string(),
-                r#type: "STRICT_DNS".to_string(),
-                lb_policy: "ROUND_ROBIN".to_string(),
-                http2: false,
-                endpoints: vec![Endpoint {
-                    address: "127.0.0.1".to_string(),
-                    port: 443,
-                }],
-            },
+            upstream("cilium_tls", "127.0.0.1", 443),
         ];
 
-        let policies = PoliciesSpec {
-            local_ratelimits: Default::default(),
-        };
+        let policies = PoliciesSpec::default();
 
-        let defaults = DefaultsSpec {
-            route_timeout: "60s".to_string(),
-            http_default_upstream: "cilium_http".to_string(),
-            tls_passthrough_upstream: "cilium_tls".to_string(),
-        };
+        let defaults = defaults("cilium_http", "cilium_tls");
 
-        let result = validate_model(&domains, &upstreams, &policies, &defaults);
+        let result = validate_model(&[], &upstreams, &policies, &defaults, &[]);
         assert!(result.is_err());
         assert!(result
             .unwrap_err()
             .to_string()
-            .contains("requires tls block"));
+            .contains("requires IP addresses"));
     }
 
     #[test]
-    fn test_validate_model_unsupported_mode() {
-        let domains = vec![DomainSpec {
-            domain: "example.com".to_string(),
-            mode: "unsupported_mode".to_string(), // not supported
-            tls: None,
-            routes: vec![],
-            http_connection_manager: None,
-            normalize_path: None,
-            merge_slashes: None,
-            aws_signing: None,
-        }];
-
+    fn test_validate_model_locality_priority_gap() {
         let upstreams = vec![
             UpstreamSpec {
-                name: "cilium_http".to_string(),
-                connect_timeout: "5s".to_string(),
-                r#type: "STRICT_DNS".to_string(),
-                lb_policy: "ROUND_ROBIN".to_string(),
-                http2: false,
-                endpoints: vec![Endpoint {
-                    address: "127.0.0.1".to_string(),
-                    port: 80,
+                localities: vec![LocalitySpec {
+                    region: Some("eu-central".to_string()),
+                    zone: None,
+                    sub_zone: None,
+                    priority: 2,
+                    load_balancing_weight: None,
+                    endpoints: vec![Endpoint {
+                        address: "127.0.0.2".to_string(),
+                        port: 80,
+                        ..Default::default()
+                    }],
                 }],
+                ..upstream("cilium_http", "127.0.0.1", 80)
             },
+            upstream("cilium_tls", "127.0.0.1", 443),
+        ];
+
+        let policies = PoliciesSpec::default();
+
+        let defaults = defaults("cilium_http", "cilium_tls");
+
+        let result = validate_model(&[], &upstreams, &policies, &defaults, &[]);
+        assert!(result.is_err());
+        assert!(result.unwrap_err().to_string().contains("contiguous"));
+    }
+
+    #[test]
+    fn test_validate_model_upstream_tls_client_cert_without_key() {
+        let upstreams = vec![
             UpstreamSpec {
-                name: "cilium_tls".to_string(),
-                connect_timeout: "5s".to_string(),
-                r#type: "STRICT_DNS".to_string(),
-                lb_policy: "ROUND_ROBIN".to_string(),
-                http2: false,
-                endpoints: vec![Endpoint {
-                    address: "127.0.0.1".to_string(),
-                    port: 443,
-                }],
+                tls: Some(UpstreamTlsSpec {
+                    trusted_ca: Some("/etc/ssl/ca.pem".to_string()),
+                    client_cert_chain: Some("/etc/envoy/client.pem".to_string()),
+                    ..Default::default()
+                }),
+                ..upstream("cilium_http", "127.0.0.1", 80)
             },
+            upstream("cilium_tls", "127.0.0.1", 443),
         ];
 
-        let policies = PoliciesSpec {
-            local_ratelimits: Default::default(),
-        };
+        let policies = PoliciesSpec::default();
 
-        let defaults = DefaultsSpec {
-            route_timeout: "60s".to_string(),
-            http_default_upstream: "cilium_http".to_string(),
-            tls_passthrough_upstream: "cilium_tls".to_string(),
-        };
+        let defaults = defaults("cilium_http", "cilium_tls");
 
-        let result = validate_model(&domains, &upstreams, &policies, &defaults);
+        let result = validate_model(&[], &upstreams, &policies, &defaults, &[]);
         assert!(result.is_err());
         assert!(result
             .unwrap_err()
             .to_string()
-            .contains("has unsupported mode"));
+            .contains("client_cert_chain and client_private_key"));
     }
 
     #[test]
-    fn test_validate_model_missing_default_upstream() {
-        let domains = vec![];
-
-        let upstreams = vec![UpstreamSpec {
-            name: "some_upstream".to_string(),
-            connect_timeout: "5s".to_string(),
-            r#type: "STRICT_DNS".to_string(),
-            lb_policy: "ROUND_ROBIN".to_string(),
-            http2: false,
-            endpoints: vec![Endpoint {
-                address: "127.0.0.1".to_string(),
-                port: 80,
-            }],
-        }];
+    fn test_validate_model_upstream_tls_without_trusted_ca() {
+        let mut upstreams = vec![
+            UpstreamSpec {
+                tls: Some(UpstreamTlsSpec::default()),
+                ..upstream("cilium_http", "127.0.0.1", 80)
+            },
+            upstream("cilium_tls", "127.0.0.1", 443),
+        ];
+        let policies = PoliciesSpec::default();
+        let defaults = defaults("cilium_http", "cilium_tls");
 
-        let policies = PoliciesSpec {
-            local_ratelimits: Default::default(),
-        };
+        let result = validate_model(&[], &upstreams, &policies, &defaults, &[]);
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("upstream cilium_http tls has no trusted_ca"));
 
-        let defaults = DefaultsSpec {
-            route_timeout: "60s".to_string(),
-            http_default_upstream: "missing_upstream".to_string(), // doesn't exist
-            tls_passthrough_upstream: "cilium_tls".to_string(),    // also missing
-        };
+        let tls = upstreams[0].tls.as_mut().unwrap();
+        tls.insecure_skip_verify = true;
+        assert!(validate_model(&[], &upstreams, &policies, &defaults, &[]).is_ok());
 
-        let result = validate_model(&domains, &upstreams, &policies, &defaults);
-        assert!(result.is_err());
-        let error_msg = result.unwrap_err().to_string();
-        assert!(
-            error_msg.contains("http_default_upstream")
-                || error_msg.contains("tls_passthrough_upstream")
+        let tls = upstreams[0].tls.as_mut().unwrap();
+        tls.trusted_ca = Some("/etc/ssl/ca.pem".to_string());
+        let result = validate_model(&[], &upstreams, &policies, &defaults, &[]);
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "upstream cilium_http tls sets both trusted_ca and insecure_skip_verify"
         );
     }
 
     #[test]
-    fn test_validate_model_upstream_with_no_endpoints() {
-        let domains = vec![];
+    fn test_validate_model_require_client_certificate_without_ca() {
+        let domains = vec![DomainSpec {
+            domain: "partner.example.com".to_string(),
+            mode: "terminate_https_443".to_string(),
+            tls: Some(TlsSpec {
+                cert_chain: Some("/path/to/cert".to_string()),
+                private_key: Some("/path/to/key".to_string()),
+                require_client_certificate: true,
+                ..Default::default()
+            }),
+            ..Default::default()
+        }];
 
         let upstreams = vec![
-            UpstreamSpec {
-                name: "empty_upstream".to_string(),
-                connect_timeout: "5s".to_string(),
-                r#type: "STRICT_DNS".to_string(),
-                lb_policy: "ROUND_ROBIN".to_string(),
-                http2: false,
-                endpoints: vec![], // no endpoints
-            },
-            UpstreamSpec {
-                name: "cilium_http".to_string(),
-                connect_timeout: "5s".to_string(),
-                r#type: "STRICT_DNS".to_string(),
-                lb_policy: "ROUND_ROBIN".to_string(),
-                http2: false,
-                endpoints: vec![Endpoint {
-                    address: "127.0.0.1".to_string(),
-                    port: 80,
-                }],
-            },
-            UpstreamSpec {
-                name: "cilium_tls".to_string(),
-                connect_timeout: "5s".to_string(),
-                r#type: "STRICT_DNS".to_string(),
-                lb_policy: "ROUND_ROBIN".to_string(),
-                http2: false,
-                endpoints: vec![Endpoint {
-                    address: "127.0.0.1".to_string(),
-                    port: 443,
-                }],
-            },
+            upstream("cilium_http", "127.0.0.1", 80),
+            upstream("cilium_tls", "127.0.0.1", 443),
         ];
 
-        let policies = PoliciesSpec {
-            local_ratelimits: Default::default(),
-        };
+        let policies = PoliciesSpec::default();
 
-        let defaults = DefaultsSpec {
-            route_timeout: "60s".to_string(),
-            http_default_upstream: "cilium_http".to_string(),
-            tls_passthrough_upstream: "cilium_tls".to_string(),
-        };
+        let defaults = defaults("cilium_http", "cilium_tls");
 
-        let result = validate_model(&domains, &upstreams, &policies, &defaults);
+        let result = validate_model(&domains, &upstreams, &policies, &defaults, &[]);
         assert!(result.is_err());
-        assert!(result.unwrap_err().to_string().contains("has no endpoints"));
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("requires tls.client_ca"));
     }
 
     #[test]
-    fn test_validate_model_route_with_unknown_upstream() {
+    fn test_validate_model_tls_min_version_above_max() {
         let domains = vec![DomainSpec {
             domain: "example.com".to_string(),
             mode: "terminate_https_443".to_string(),
             tls: Some(TlsSpec {
-                cert_chain: "/path/to/cert".to_string(),
-                private_key: "/path/to/key".to_string(),
-            }),
-            routes: vec![RouteSpec {
-                m: MatchSpec {
-                    prefix: Some("/api".to_string()),
-                    path: None,
-                    headers: vec![],
+                cert_chain: Some("/path/to/cert".to_string()),
+                private_key: Some("/path/to/key".to_string()),
+                params: TlsParamsSpec {
+                    tls_minimum_protocol_version: Some(TlsProtocolVersion::TlsV1_3),
+                    ..Default::default()
                 },
-                to_upstream: Some("unknown_backend".to_string()), // doesn't exist
-                timeout: Some("30s".to_string()),
-                per_filter_config: None,
-                prefix_rewrite: None,
-                direct_response: None,
-            }],
-            http_connection_manager: None,
-            normalize_path: None,
-            merge_slashes: None,
-            aws_signing: None,
+                ..Default::default()
+            }),
+            ..Default::default()
         }];
 
         let upstreams = vec![
-            UpstreamSpec {
-                name: "cilium_http".to_string(),
-                connect_timeout: "5s".to_string(),
-                r#type: "STRICT_DNS".to_string(),
-                lb_policy: "ROUND_ROBIN".to_string(),
-                http2: false,
-                endpoints: vec![Endpoint {
-                    address: "127.0.0.1".to_string(),
-                    port: 80,
-                }],
-            },
-            UpstreamSpec {
-                name: "cilium_tls".to_string(),
-                connect_timeout: "5s".to_string(),
-                r#type: "STRICT_DNS".to_string(),
-                lb_policy: "ROUND_ROBIN".to_string(),
-                http2: false,
-                endpoints: vec![Endpoint {
-                    address: "127.0.0.1".to_string(),
-                    port: 443,
-                }],
-            },
+            upstream("cilium_http", "127.0.0.1", 80),
+            upstream("cilium_tls", "127.0.0.1", 443),
         ];
 
-        let policies = PoliciesSpec {
-            local_ratelimits: Default::default(),
-        };
+        let policies = PoliciesSpec::default();
 
+        // Global cap of TLS 1.2 conflicts with the domain's TLS 1.3 minimum
         let defaults = DefaultsSpec {
-            route_timeout: "60s".to_string(),
-            http_default_upstream: "cilium_http".to_string(),
-            tls_passthrough_upstream: "cilium_tls".to_string(),
+            tls: TlsParamsSpec {
+                tls_maximum_protocol_version: Some(TlsProtocolVersion::TlsV1_2),
+                ..Default::default()
+            },
+            ..defaults("cilium_http", "cilium_tls")
         };
 
-        let result = validate_model(&domains, &upstreams, &policies, &defaults);
+        let result = validate_model(&domains, &upstreams, &policies, &defaults, &[]);
         assert!(result.is_err());
         assert!(result
             .unwrap_err()
             .to_string()
-            .contains("route references unknown upstream"));
+            .contains("is above tls_maximum_protocol_version"));
     }
 
     #[test]
-    fn test_validate_model_route_with_unknown_rate_limit_policy() {
+    fn test_validate_model_unknown_cert_set() {
         let domains = vec![DomainSpec {
-            domain: "example.com".to_string(),
+            domain: "app.example.com".to_string(),
             mode: "terminate_https_443".to_string(),
             tls: Some(TlsSpec {
-                cert_chain: "/path/to/cert".to_string(),
-                private_key: "/path/to/key".to_string(),
+                cert_set: Some("missing_wildcard".to_string()), // not in certs/
+                ..Default::default()
             }),
-            routes: vec![RouteSpec {
-                m: MatchSpec {
-                    prefix: Some("/api".to_string()),
-                    path: None,
-                    headers: vec![],
-                },
-                to_upstream: Some("api_backend".to_string()),
-                timeout: Some("30s".to_string()),
-                per_filter_config: Some(PerFilterConfigRef {
-                    local_ratelimit: Some("unknown_policy".to_string()), // doesn't exist
-                }),
-                prefix_rewrite: None,
-                direct_response: None,
-            }],
-            http_connection_manager: None,
-            normalize_path: None,
-            merge_slashes: None,
-            aws_signing: None,
+            ..Default::default()
         }];
 
         let upstreams = vec![
-            UpstreamSpec {
-                name: "api_backend".to_string(),
-                connect_timeout: "5s".to_string(),
-                r#type: "STRICT_DNS".to_string(),
-                lb_policy: "ROUND_ROBIN".to_string(),
-                http2: false,
-                endpoints: vec![Endpoint {
-                    address: "127.0.0.1".to_string(),
-                    port: 8080,
-                }],
-            },
-            UpstreamSpec {
-                name: "cilium_http".to_string(),
-                connect_timeout: "5s".to_string(),
-                r#type: "STRICT_DNS".to_string(),
-                lb_policy: "ROUND_ROBIN".to_string(),
-                http2: false,
-                endpoints: vec![Endpoint {
-                    address: "127.0.0.1".to_string(),
-                    port: 80,
-                }],
-            },
-            UpstreamSpec {
-                name: "cilium_tls".to_string(),
-                connect_timeout: "5s".to_string(),
-                r#type: "STRICT_DNS".to_string(),
-                lb_policy: "ROUND_ROBIN".to_string(),
-                http2: false,
-                endpoints: vec![Endpoint {
-                    address: "127.0.0.1".to_string(),
-                    port: 443,
-                }],
-            },
+            upstream("cilium_http", "127.0.0.1", 80),
+            upstream("cilium_tls", "127.0.0.1", 443),
         ];
 
-        let policies = PoliciesSpec {
-            local_ratelimits: Default::default(), // empty - no policies
-        };
+        let policies = PoliciesSpec::default();
 
-        let defaults = DefaultsSpec {
-            route_timeout: "60s".to_string(),
-            http_default_upstream: "cilium_http".to_string(),
-            tls_passthrough_upstream: "cilium_tls".to_string(),
-        };
+        let defaults = defaults("cilium_http", "cilium_tls");
 
-        let result = validate_model(&domains, &upstreams, &policies, &defaults);
+        let cert_sets = vec![CertSetSpec {
+            name: "wildcard_example_com".to_string(),
+            certificates: vec![CertificateSpec {
+                cert_chain: "/certs/wildcard.pem".to_string(),
+                private_key: "/certs/wildcard.key".to_string(),
+                ocsp_staple: None,
+            }],
+        }];
+
+        let result = validate_model(&domains, &upstreams, &policies, &defaults, &cert_sets);
         assert!(result.is_err());
         assert!(result
             .unwrap_err()
             .to_string()
-            .contains("route references unknown local_ratelimit policy"));
+            .contains("unknown certificate set"));
     }
 }
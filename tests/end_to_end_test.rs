@@ -5,7 +5,7 @@ use tempfile::TempDir;
 fn test_cli_help() {
     // Test that the CLI shows help without crashing
     let output = Command::new("cargo")
-        .args(&["run", "--", "--help"])
+        .args(["run", "--", "--help"])
         .output()
         .expect("Failed to execute help command");
 
@@ -23,7 +23,7 @@ fn test_end_to_end_workflow_with_temp_workspace() {
 
     // Initialize a workspace using the CLI
     let init_output = Command::new("cargo")
-        .args(&[
+        .args([
             "run",
             "--",
             "init",
@@ -53,7 +53,7 @@ fn test_end_to_end_workflow_with_temp_workspace() {
 fn test_invalid_command_error_handling() {
     // Test that invalid commands produce appropriate error messages
     let output = Command::new("cargo")
-        .args(&["run", "--", "nonexistent-command"])
+        .args(["run", "--", "nonexistent-command"])
         .output()
         .expect("Failed to execute invalid command");
 